@@ -7,8 +7,9 @@ mod scan;
 mod soup;
 mod utils;
 
-use scan::dir_scan;
-use soup::model::SoupContexts;
+use parse::{cargo, package_json};
+use scan::dir_scan::{self, ScanOptions};
+use soup::{change_set::ChangeSet, model::SoupContexts};
 
 /// Scans a given repository for software of unknown provenance (SOUP) and outputs them in a file.
 #[derive(Parser)]
@@ -29,6 +30,48 @@ struct Cli {
     // Key to add in meta property
     #[clap(short = 'm', long = "meta-key")]
     meta_keys: Vec<String>,
+
+    /// Include transitive dependencies from lock files
+    #[clap(short = 't', long = "include-transitive")]
+    include_transitive: bool,
+
+    /// Kind of Cargo dependencies to include
+    #[clap(
+        long = "cargo-dependency-kind",
+        arg_enum,
+        default_values = &["normal"]
+    )]
+    cargo_kinds: Vec<cargo::DependencyKind>,
+
+    /// Kind of npm dependencies to include
+    #[clap(
+        long = "npm-dependency-kind",
+        arg_enum,
+        default_values = &["normal", "optional", "peer"]
+    )]
+    npm_kinds: Vec<package_json::DependencyKind>,
+
+    /// Value of a Dockerfile ARG, given as NAME=VALUE
+    #[clap(long = "build-arg", parse(try_from_str = parse_build_arg))]
+    build_args: Vec<(String, String)>,
+
+    /// Verify that output file is up to date, without writing to it
+    #[clap(short = 'c', long = "check")]
+    check: bool,
+
+    /// Verify that all meta keys given by --meta-key are filled in, without writing to output file
+    #[clap(short = 'v', long = "validate-meta")]
+    validate_meta: bool,
+
+    /// Print a report of changes to the output file
+    #[clap(short = 'r', long = "report", arg_enum)]
+    report: Option<ReportFormat>,
+}
+
+#[derive(clap::ArgEnum, Clone)]
+enum ReportFormat {
+    Text,
+    Json,
 }
 
 fn main() {
@@ -52,12 +95,22 @@ fn main() {
 
     let root_dir = parse_root_dir(args.root_dir);
     let exclude_dirs = args.exclude_dirs;
-    let default_meta = args
-        .meta_keys
-        .into_iter()
-        .map(|meta_key| (meta_key, json!("")))
+    let meta_keys = args.meta_keys;
+    let default_meta = meta_keys
+        .iter()
+        .map(|meta_key| (meta_key.to_owned(), json!("")))
         .collect::<Map<String, Value>>();
-    let scanned_contexts = match dir_scan::scan(&root_dir, &exclude_dirs, default_meta) {
+    let scanned_contexts = match dir_scan::scan(
+        &root_dir,
+        &exclude_dirs,
+        default_meta,
+        &ScanOptions {
+            include_transitive: args.include_transitive,
+            cargo_kinds: args.cargo_kinds,
+            npm_kinds: args.npm_kinds,
+            build_args: args.build_args.into_iter().collect(),
+        },
+    ) {
         Ok(result) => result,
         Err(e) => {
             eprintln!(
@@ -69,7 +122,25 @@ fn main() {
         }
     };
 
-    current_contexts.apply(scanned_contexts);
+    let change_set = current_contexts.apply(scanned_contexts);
+    if let Some(format) = args.report {
+        print_report(&change_set, format);
+    }
+
+    if args.check || args.validate_meta {
+        let mut passed = true;
+        if args.check {
+            passed &= check_up_to_date(&change_set, &output_file);
+        }
+        if args.validate_meta {
+            passed &= validate_meta(&current_contexts, &meta_keys);
+        }
+        if !passed {
+            process::exit(1);
+        }
+        return;
+    }
+
     if let Err(e) = current_contexts.write_to_file(&output_file) {
         eprintln!("Error while writing to file: {}", e);
         process::exit(1);
@@ -94,6 +165,13 @@ fn parse_root_dir(dir: Option<path::PathBuf>) -> path::PathBuf {
     root_dir
 }
 
+fn parse_build_arg(build_arg: &str) -> Result<(String, String), String> {
+    match build_arg.split_once('=') {
+        Some((name, value)) => Ok((name.to_owned(), value.to_owned())),
+        None => Err(format!("expected NAME=VALUE, got: {}", build_arg)),
+    }
+}
+
 fn parse_output_file(file_path: path::PathBuf) -> path::PathBuf {
     if file_path.exists() && !file_path.is_file() {
         eprintln!("Invalid output file: {}", file_path.display());
@@ -101,3 +179,45 @@ fn parse_output_file(file_path: path::PathBuf) -> path::PathBuf {
     }
     file_path
 }
+
+fn print_report(change_set: &ChangeSet, format: ReportFormat) {
+    match format {
+        ReportFormat::Text => print!("{}", change_set),
+        ReportFormat::Json => match serde_json::to_string_pretty(change_set) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Not able to serialize report to json: {}", e);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn check_up_to_date(change_set: &ChangeSet, output_file: &path::Path) -> bool {
+    if change_set.is_empty() {
+        return true;
+    }
+    eprintln!("Output file is not up to date: {}", output_file.display());
+    eprint!("{}", change_set);
+    false
+}
+
+fn validate_meta(contexts: &SoupContexts, meta_keys: &[String]) -> bool {
+    let missing_meta = contexts.missing_meta(meta_keys);
+    if missing_meta.is_empty() {
+        return true;
+    }
+    eprintln!("Missing meta values:");
+    for (path, soups) in missing_meta {
+        eprintln!("  {}", path);
+        for (soup, keys) in soups {
+            let keys = keys
+                .iter()
+                .map(|key| key.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            eprintln!("    {} ({}): {}", soup.name, soup.version, keys);
+        }
+    }
+    false
+}
@@ -0,0 +1,127 @@
+use super::{shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct Apk {}
+
+const OPTIONS_WITH_VALUE: [&str; 10] = [
+    "-t",
+    "--virtual",
+    "-X",
+    "--repository",
+    "-p",
+    "--root",
+    "--arch",
+    "--keys-dir",
+    "--cache-dir",
+    "--repositories-file",
+];
+
+impl SoupParse for Apk {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        let content = shell::normalize(content);
+        for line in content.lines() {
+            for command in shell::commands(line) {
+                let packages = match shell::subcommand_arguments(
+                    &command,
+                    &["apk"],
+                    &["add"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(packages) => packages,
+                    None => continue,
+                };
+                for package in packages {
+                    if let Some(soup) = package_soup(package, default_meta) {
+                        result.insert(soup);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Packages are given as `name`, `name=version` or with a constraint such as `name~1.2`, and
+/// may be tagged with a repository as in `name@edge`.
+fn package_soup(package: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    if package.starts_with('$') || package.ends_with(".apk") {
+        return None;
+    }
+    let (name, version) = match package.find(['=', '~', '<', '>']) {
+        Some(index) => {
+            let version = &package[index..];
+            (
+                &package[..index],
+                version.strip_prefix('=').unwrap_or(version),
+            )
+        }
+        None => (package, "unknown"),
+    };
+    let name = name.split('@').next().unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(Soup {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        meta: default_meta.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test]
+    fn multiple_packages() {
+        let input = r#"RUN apk update \
+    && apk add --no-cache --virtual .build-deps \
+        curl=7.83.1-r3 \
+        git \
+        openssl~1.1 \
+    && rm -rf /var/cache/apk/*"#;
+        let result = Apk {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("curl", "7.83.1-r3"),
+                soup("git", "unknown"),
+                soup("openssl", "~1.1"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("apk add curl@edge")]
+    #[test_case("apk add -X http://dl-cdn.alpinelinux.org/alpine/edge/main curl")]
+    #[test_case("apk --no-cache add curl $EXTRA_PACKAGES")]
+    fn options_and_variables(input: &str) {
+        let result = Apk {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("curl", "unknown")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("apk update")]
+    #[test_case("apk del curl")]
+    fn no_packages(input: &str) {
+        let result = Apk {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+}
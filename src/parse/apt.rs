@@ -1,26 +1,20 @@
-use super::SoupParse;
+use super::{shell, SoupParse};
 use crate::soup::model::{Soup, SoupSourceParseError};
-use lazy_static::lazy_static;
-use regex::{Regex, RegexSet};
 use serde_json::{Map, Value};
 use std::collections::BTreeSet;
 
 pub struct Apt {}
 
-static PATTERNS: [&str; 2] = [
-    r"apt(?:\-get)? install (?:(?:\-[a-zA-Z] )|(?:\-\-[a-zA-Z\-]+ ))*(?P<name>[a-zA-Z0-9\._\-]+)=(?P<version>[a-zA-Z0-9\.\-_]+)",
-    r"apt(?:\-get)? install (?:(?:\-[a-zA-Z] )|(?:\-\-[a-zA-Z\-]+ ))*(?P<name>[a-zA-Z0-9\._\-]+)",
+const OPTIONS_WITH_VALUE: [&str; 8] = [
+    "-o",
+    "--option",
+    "-c",
+    "--config-file",
+    "-t",
+    "--target-release",
+    "-a",
+    "--host-architecture",
 ];
-lazy_static! {
-    static ref PATTERN_SET: RegexSet = RegexSet::new(&PATTERNS).unwrap();
-    static ref REGEXES: Vec<Regex> = PATTERN_SET
-        .patterns()
-        .iter()
-        .map(|pat| Regex::new(pat).unwrap())
-        .collect();
-    static ref LINE_CONTINUATION: Regex = Regex::new(r"\\.*\n|\r\n").unwrap();
-    static ref MULTI_SPACE: Regex = Regex::new(r"[ \t]+").unwrap();
-}
 
 impl SoupParse for Apt {
     fn soups(
@@ -29,23 +23,22 @@ impl SoupParse for Apt {
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
         let mut result: BTreeSet<Soup> = BTreeSet::new();
-        let content = normalize(content);
+        let content = shell::normalize(content);
         for line in content.lines() {
-            let matching_patterns = PATTERN_SET
-                .matches(line)
-                .into_iter()
-                .map(|match_id| &REGEXES[match_id])
-                .collect::<Vec<&Regex>>();
-            if let Some(pattern) = matching_patterns.first() {
-                if let Some(captures) = pattern.captures(line) {
-                    result.insert(Soup {
-                        name: named_capture(&captures, "name")?,
-                        version: match named_capture(&captures, "version") {
-                            Ok(version) => version,
-                            Err(_e) => "unknown".to_owned(),
-                        },
-                        meta: default_meta.clone(),
-                    });
+            for command in shell::commands(line) {
+                let packages = match shell::subcommand_arguments(
+                    &command,
+                    &["apt", "apt-get"],
+                    &["install"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(packages) => packages,
+                    None => continue,
+                };
+                for package in packages {
+                    if let Some(soup) = package_soup(package, default_meta) {
+                        result.insert(soup);
+                    }
                 }
             }
         }
@@ -53,24 +46,31 @@ impl SoupParse for Apt {
     }
 }
 
-fn normalize(input: &str) -> String {
-    let result = LINE_CONTINUATION.replace_all(input, " ");
-    let result = MULTI_SPACE.replace_all(&result, " ");
-    result.to_string()
-}
-
-fn named_capture(captures: &regex::Captures, name: &str) -> Result<String, SoupSourceParseError> {
-    match captures.name(name) {
-        Some(value) => Ok(value.as_str().to_owned()),
-        None => Err(SoupSourceParseError {
-            message: "Unable to parse apt install statement".to_owned(),
-        }),
+/// Packages are given as `name`, `name=version` or `name/release`. Packages given only by a
+/// shell variable are left out, since their names are not known.
+fn package_soup(package: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    if package.starts_with('$') {
+        return None;
     }
+    let (name, version) = match package.split_once('=') {
+        Some((name, version)) => (name, version),
+        None => (package, "unknown"),
+    };
+    let name = name.split('/').next().unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(Soup {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        meta: default_meta.clone(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::soup;
     use test_case::test_case;
 
     #[test_case("apt install curl=7.81.0-1ubuntu1.3")]
@@ -144,4 +144,67 @@ mod tests {
             soup
         );
     }
+
+    #[test]
+    fn multiple_packages() {
+        let input = r#"RUN apt-get update \
+    && apt-get install -y --no-install-recommends \
+        curl=7.81.0-1ubuntu1.3 \
+        git \
+        ca-certificates \
+    && rm -rf /var/lib/apt/lists/*"#;
+        let result = Apt {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("ca-certificates", "unknown"),
+                soup("curl", "7.81.0-1ubuntu1.3"),
+                soup("git", "unknown"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn escaped_characters_on_line() {
+        let input = r#"RUN printf "a\n" > /f && apt-get install -y curl wget"#;
+        let result = Apt {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("curl", "unknown"), soup("wget", "unknown")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("apt-get -o Dpkg::Options::=--force-confold install -y curl")]
+    #[test_case("apt-get install -o Acquire::Retries=3 -y curl")]
+    #[test_case("DEBIAN_FRONTEND=noninteractive apt-get install -y curl $EXTRA_PACKAGES")]
+    #[test_case("apt-get install -t bullseye-backports curl/bullseye-backports")]
+    #[test_case("apt-get update && apt-get install -y curl > /dev/null 2>&1")]
+    fn options_and_variables(input: &str) {
+        let result = Apt {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("curl", "unknown")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn pinned_version_from_variable() {
+        let result = Apt {}.soups("apt-get install -y curl=${CURL_VERSION}", &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("curl", "${CURL_VERSION}")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
 }
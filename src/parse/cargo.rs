@@ -4,13 +4,134 @@ use serde::Deserialize;
 use serde_json::{Map, Value};
 use std::collections::{BTreeSet, HashMap};
 
-pub struct Cargo {}
+pub struct Cargo {
+    pub kinds: Vec<DependencyKind>,
+    pub workspace_manifest: Option<String>,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DependencyKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+impl DependencyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Build => "build",
+            DependencyKind::Dev => "dev",
+        }
+    }
+}
+
+impl Default for Cargo {
+    fn default() -> Self {
+        Cargo {
+            kinds: vec![DependencyKind::Normal],
+            workspace_manifest: None,
+        }
+    }
+}
 
 #[derive(Deserialize)]
 struct Content {
+    package: Option<Package>,
+    #[serde(flatten)]
+    dependencies: Dependencies,
+    target: Option<HashMap<String, Dependencies>>,
+    workspace: Option<Workspace>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Workspace {
+    members: Option<Vec<String>>,
     dependencies: Option<HashMap<String, toml::Value>>,
 }
 
+#[derive(Deserialize)]
+struct Dependencies {
+    dependencies: Option<HashMap<String, toml::Value>>,
+    #[serde(rename = "build-dependencies", alias = "build_dependencies")]
+    build_dependencies: Option<HashMap<String, toml::Value>>,
+    #[serde(rename = "dev-dependencies", alias = "dev_dependencies")]
+    dev_dependencies: Option<HashMap<String, toml::Value>>,
+}
+
+impl Dependencies {
+    fn of_kind(&self, kind: DependencyKind) -> Option<&HashMap<String, toml::Value>> {
+        match kind {
+            DependencyKind::Normal => self.dependencies.as_ref(),
+            DependencyKind::Build => self.build_dependencies.as_ref(),
+            DependencyKind::Dev => self.dev_dependencies.as_ref(),
+        }
+    }
+}
+
+pub fn is_workspace_manifest(content: &str) -> bool {
+    match toml::from_str::<Content>(content) {
+        Ok(content) => content.workspace.is_some(),
+        Err(_e) => false,
+    }
+}
+
+/// Returns the member patterns of a workspace manifest. Invalid content has no members.
+pub fn workspace_members(content: &str) -> Vec<String> {
+    match toml::from_str::<Content>(content) {
+        Ok(content) => content
+            .workspace
+            .and_then(|w| w.members)
+            .unwrap_or_default(),
+        Err(_e) => vec![],
+    }
+}
+
+/// Returns the package name of a manifest, if any, and the kind of each of its dependencies
+/// of the given kinds, by the name of the package depended on. A dependency listed in several
+/// sections gets the first kind in the order Normal, Build, Dev.
+pub fn dependency_kinds(
+    content: &str,
+    kinds: &[DependencyKind],
+) -> Result<(Option<String>, HashMap<String, DependencyKind>), SoupSourceParseError> {
+    let content: Content = match toml::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid Cargo.toml ({})", e),
+            });
+        }
+    };
+    let mut dependency_kinds = HashMap::new();
+    for kind in [
+        DependencyKind::Normal,
+        DependencyKind::Build,
+        DependencyKind::Dev,
+    ] {
+        if !kinds.contains(&kind) {
+            continue;
+        }
+        let sections = std::iter::once(&content.dependencies)
+            .chain(content.target.iter().flat_map(|targets| targets.values()))
+            .filter_map(|dependencies| dependencies.of_kind(kind));
+        for dependencies in sections {
+            for (dependency, value) in dependencies {
+                let name = value
+                    .get("package")
+                    .and_then(toml::Value::as_str)
+                    .unwrap_or(dependency);
+                dependency_kinds.entry(name.to_owned()).or_insert(kind);
+            }
+        }
+    }
+    Ok((content.package.and_then(|p| p.name), dependency_kinds))
+}
+
 impl SoupParse for Cargo {
     fn soups(
         &self,
@@ -25,41 +146,130 @@ impl SoupParse for Cargo {
                 });
             }
         };
-        match content.dependencies {
-            None => Ok(BTreeSet::new()),
-            Some(dependencies) => dependencies
-                .into_iter()
-                .map(|(dependency, value)| match value {
-                    toml::Value::String(version) => Ok(Soup {
-                        name: dependency,
-                        version,
-                        meta: default_meta.clone(),
-                    }),
-                    toml::Value::Table(table) => match table.get("version") {
-                        Some(version) => Ok(Soup {
-                            name: dependency.to_owned(),
-                            version: match version.as_str() {
-                                Some(v) => v.to_owned(),
-                                None => {
-                                    return Err(SoupSourceParseError {
-                                        message: format!("Invalid version for: {}", dependency),
-                                    });
-                                }
-                            },
-                            meta: default_meta.clone(),
-                        }),
-                        None => {
-                            return Err(SoupSourceParseError {
-                                message: format!("Missing version for: {}", dependency),
-                            });
-                        }
-                    },
-                    _ => Err(SoupSourceParseError {
-                        message: format!("Malformed dependency: {}", dependency),
-                    }),
-                })
-                .collect::<Result<BTreeSet<Soup>, _>>(),
+        let workspace_dependencies = match &self.workspace_manifest {
+            Some(manifest) if content.workspace.is_none() => {
+                match toml::from_str::<Content>(manifest) {
+                    Ok(manifest) => manifest.workspace.and_then(|w| w.dependencies),
+                    Err(e) => {
+                        return Err(SoupSourceParseError {
+                            message: format!("Invalid workspace Cargo.toml ({})", e),
+                        });
+                    }
+                }
+            }
+            _ => content.workspace.and_then(|w| w.dependencies),
+        }
+        .unwrap_or_default();
+        let mut targets = content
+            .target
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<(String, Dependencies)>>();
+        targets.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut soups = BTreeSet::new();
+        for kind in [
+            DependencyKind::Normal,
+            DependencyKind::Build,
+            DependencyKind::Dev,
+        ] {
+            if !self.kinds.contains(&kind) {
+                continue;
+            }
+            if let Some(dependencies) = content.dependencies.of_kind(kind) {
+                soups.extend(parse_dependencies(
+                    dependencies,
+                    &workspace_dependencies,
+                    kind,
+                    None,
+                    default_meta,
+                )?);
+            }
+            for (target, target_dependencies) in &targets {
+                if let Some(dependencies) = target_dependencies.of_kind(kind) {
+                    soups.extend(parse_dependencies(
+                        dependencies,
+                        &workspace_dependencies,
+                        kind,
+                        Some(target),
+                        default_meta,
+                    )?);
+                }
+            }
         }
+        Ok(soups)
+    }
+}
+
+fn parse_dependencies(
+    dependencies: &HashMap<String, toml::Value>,
+    workspace_dependencies: &HashMap<String, toml::Value>,
+    kind: DependencyKind,
+    target: Option<&str>,
+    default_meta: &Map<String, Value>,
+) -> Result<Vec<Soup>, SoupSourceParseError> {
+    let mut meta = default_meta.clone();
+    meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+    if let Some(target) = target {
+        meta.insert("target".to_owned(), Value::from(target));
+    }
+    let mut soups = Vec::new();
+    for (dependency, value) in dependencies {
+        let value = match value.get("workspace").and_then(toml::Value::as_bool) {
+            Some(true) => match workspace_dependencies.get(dependency) {
+                Some(workspace_value) => workspace_value,
+                None => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Missing workspace dependency for: {}", dependency),
+                    });
+                }
+            },
+            _ => value,
+        };
+        if let Some(version) = dependency_version(dependency, value)? {
+            soups.push(Soup {
+                name: dependency.to_owned(),
+                version,
+                meta: meta.clone(),
+            });
+        }
+    }
+    Ok(soups)
+}
+
+/// Returns `None` for path dependencies, and the git reference for git dependencies.
+fn dependency_version(
+    dependency: &str,
+    value: &toml::Value,
+) -> Result<Option<String>, SoupSourceParseError> {
+    let table = match value {
+        toml::Value::String(version) => return Ok(Some(version.to_owned())),
+        toml::Value::Table(table) => table,
+        _ => {
+            return Err(SoupSourceParseError {
+                message: format!("Malformed dependency: {}", dependency),
+            });
+        }
+    };
+    if table.contains_key("path") {
+        return Ok(None);
+    }
+    if table.contains_key("git") {
+        let reference = ["rev", "tag", "branch"]
+            .iter()
+            .find_map(|key| table.get(*key).and_then(toml::Value::as_str));
+        return Ok(Some(reference.unwrap_or("unknown").to_owned()));
+    }
+    match table.get("version") {
+        Some(version) => match version.as_str() {
+            Some(v) => Ok(Some(v.to_owned())),
+            None => Err(SoupSourceParseError {
+                message: format!("Invalid version for: {}", dependency),
+            }),
+        },
+        None => Err(SoupSourceParseError {
+            message: format!("Missing version for: {}", dependency),
+        }),
     }
 }
 
@@ -81,7 +291,7 @@ serde = { version = "1.0.137", features = ["derive"] }
     "#
     )]
     fn simple_dependency(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(
@@ -101,7 +311,7 @@ serde = { version = "1.0.137", features = ["derive"] }
 serde_json = { version = "1.0.82", features = ["preserve_order"] }
 quick-xml = "0.23.0"
         "#;
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -124,10 +334,173 @@ quick-xml = "0.23.0"
         );
     }
 
+    const ALL_KINDS: &str = r#"
+[dependencies]
+serde = "1.0.137"
+
+[build-dependencies]
+cc = "1.0.73"
+
+[dev-dependencies]
+test-case = "2.2.0"
+serde = "1.0.137"
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3.9"
+
+[target.'cfg(unix)'.dev-dependencies]
+tempfile = "3.3.0"
+    "#;
+
+    #[test_case(vec![DependencyKind::Normal], vec!["serde", "winapi"])]
+    #[test_case(vec![DependencyKind::Build], vec!["cc"])]
+    #[test_case(vec![DependencyKind::Dev], vec!["serde", "tempfile", "test-case"])]
+    #[test_case(
+        vec![DependencyKind::Normal, DependencyKind::Build, DependencyKind::Dev],
+        vec!["cc", "serde", "tempfile", "test-case", "winapi"]
+    )]
+    fn dependency_kinds(kinds: Vec<DependencyKind>, expected_names: Vec<&str>) {
+        let result = Cargo {
+            kinds,
+            ..Cargo::default()
+        }
+        .soups(ALL_KINDS, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(expected_names, names);
+    }
+
+    #[test]
+    fn dependency_kind_meta() {
+        let result = Cargo {
+            kinds: vec![DependencyKind::Normal, DependencyKind::Dev],
+            ..Cargo::default()
+        }
+        .soups(ALL_KINDS, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        let serde = soups.iter().find(|soup| soup.name == "serde").unwrap();
+        assert_eq!(
+            Some(&Value::from("normal")),
+            serde.meta.get("dependency-kind")
+        );
+        assert_eq!(None, serde.meta.get("target"));
+        let tempfile = soups.iter().find(|soup| soup.name == "tempfile").unwrap();
+        assert_eq!(
+            Some(&Value::from("dev")),
+            tempfile.meta.get("dependency-kind")
+        );
+        assert_eq!(Some(&Value::from("cfg(unix)")), tempfile.meta.get("target"));
+    }
+
+    #[test]
+    fn workspace_dependencies() {
+        let workspace_manifest = r#"
+[workspace]
+members = ["some-crate"]
+
+[workspace.dependencies]
+serde = { version = "1.0.137", features = ["derive"] }
+regex = "1.6.0"
+        "#;
+        let input = r#"
+[dependencies]
+serde = { workspace = true }
+regex.workspace = true
+        "#;
+        let result = Cargo {
+            workspace_manifest: Some(workspace_manifest.to_owned()),
+            ..Cargo::default()
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "regex".to_owned(),
+                    version: "1.6.0".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "serde".to_owned(),
+                    version: "1.0.137".to_owned(),
+                    meta: Map::new()
+                }
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test]
+    fn workspace_root_dependencies() {
+        let input = r#"
+[workspace.dependencies]
+serde = "1.0.137"
+
+[dependencies]
+serde = { workspace = true }
+        "#;
+        let result = Cargo::default().soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        assert_eq!("1.0.137", soups.into_iter().next().unwrap().version);
+    }
+
+    #[test]
+    fn missing_workspace_dependency() {
+        let input = r#"
+[dependencies]
+serde = { workspace = true }
+        "#;
+        let result = Cargo::default().soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
+    #[test_case(
+        r#"some-lib = { git = "https://github.com/some/lib", rev = "9f35b8e" }"#,
+        "9f35b8e"
+    )]
+    #[test_case(
+        r#"some-lib = { git = "https://github.com/some/lib", tag = "v1.0.0" }"#,
+        "v1.0.0"
+    )]
+    #[test_case(
+        r#"some-lib = { git = "https://github.com/some/lib", branch = "main" }"#,
+        "main"
+    )]
+    #[test_case(r#"some-lib = { git = "https://github.com/some/lib" }"#, "unknown")]
+    fn git_dependency(dependency: &str, expected_version: &str) {
+        let input = format!("[dependencies]\n{}", dependency);
+        let result = Cargo::default().soups(&input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("some-lib", soup.name);
+        assert_eq!(expected_version, soup.version);
+    }
+
+    #[test_case(r#"some-lib = { path = "../some-lib" }"#)]
+    #[test_case(r#"some-lib = { path = "../some-lib", version = "0.1.0" }"#)]
+    fn path_dependency(dependency: &str) {
+        let input = format!("[dependencies]\n{}", dependency);
+        let result = Cargo::default().soups(&input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
     #[test_case("[dependencies]")]
     #[test_case("")]
     fn no_dependencies(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
@@ -159,7 +532,25 @@ serde_json = { version = { verzion = "1.0.82" }, features = ["preserve_order"] }
     )]
     #[test_case(r#"dependencies = "serde""#)]
     fn missing_or_invalid_version(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_err());
     }
+
+    #[test]
+    fn workspace_members() {
+        let input = r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+"#;
+        assert_eq!(
+            vec!["crates/*", "tools/cli"],
+            super::workspace_members(input)
+        );
+    }
+
+    #[test_case("[package]\nname = \"some-crate\"")]
+    #[test_case("[workspace")]
+    fn no_workspace_members(input: &str) {
+        assert_eq!(0, super::workspace_members(input).len());
+    }
 }
@@ -0,0 +1,341 @@
+use super::{
+    cargo::{self, DependencyKind},
+    SoupParse,
+};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Direct dependencies are those of the local packages in the lock file. When the manifests
+/// of the workspace are given, only their dependencies of the given kinds are included.
+pub struct CargoLock {
+    pub include_transitive: bool,
+    pub kinds: Vec<DependencyKind>,
+    pub manifests: Vec<String>,
+}
+
+impl Default for CargoLock {
+    fn default() -> Self {
+        CargoLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            manifests: vec![],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Content {
+    package: Option<Vec<Package>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+    source: Option<String>,
+    dependencies: Option<Vec<String>>,
+}
+
+impl SoupParse for CargoLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match toml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Cargo.lock ({})", e),
+                });
+            }
+        };
+        let packages = content.package.unwrap_or_default();
+        let external_packages = packages
+            .iter()
+            .filter(|package| package.source.is_some())
+            .collect::<Vec<&Package>>();
+        if self.include_transitive {
+            return Ok(external_packages
+                .into_iter()
+                .map(|package| Soup {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    meta: default_meta.clone(),
+                })
+                .collect());
+        }
+
+        let mut manifest_dependencies = HashMap::new();
+        for manifest in &self.manifests {
+            if let (Some(name), dependencies) = cargo::dependency_kinds(manifest, &self.kinds)? {
+                manifest_dependencies.insert(name, dependencies);
+            }
+        }
+        packages
+            .iter()
+            .filter(|package| package.source.is_none())
+            .flat_map(|package| {
+                let kinds = manifest_dependencies.get(&package.name);
+                package
+                    .dependencies
+                    .iter()
+                    .flatten()
+                    .map(move |dependency| (kinds, dependency))
+            })
+            .filter_map(|(kinds, dependency)| {
+                let mut parts = dependency.split_whitespace();
+                let name = parts.next()?;
+                let version = parts.next();
+                let kind = match kinds {
+                    Some(kinds) => Some(*kinds.get(name)?),
+                    None => None,
+                };
+                let matching_packages = external_packages
+                    .iter()
+                    .filter(|package| package.name == name)
+                    .filter(|package| match version {
+                        Some(version) => package.version == version,
+                        None => true,
+                    })
+                    .collect::<Vec<&&Package>>();
+                match matching_packages.len() {
+                    0 => None,
+                    1 => {
+                        let mut meta = default_meta.clone();
+                        if let Some(kind) = kind {
+                            meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+                        }
+                        Some(Ok(Soup {
+                            name: name.to_owned(),
+                            version: matching_packages[0].version.to_owned(),
+                            meta,
+                        }))
+                    }
+                    _ => Some(Err(SoupSourceParseError {
+                        message: format!("Ambiguous dependency: {}", dependency),
+                    })),
+                }
+            })
+            .collect::<Result<BTreeSet<Soup>, SoupSourceParseError>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCK_FILE: &str = r#"
+version = 3
+
+[[package]]
+name = "aho-corasick"
+version = "0.7.18"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "1e37cfd5e7657ada45f742d6e99ca5788580b5c529dc78faf11ece6dc702656f"
+dependencies = [
+ "memchr",
+]
+
+[[package]]
+name = "memchr"
+version = "2.5.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "2dffe52ecf27772e601905b7522cb4ef790d2cc203488bbd0e2fe85fcb74566d"
+
+[[package]]
+name = "regex"
+version = "1.6.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "4c4eb3267174b8c6c2f654116623910a0fef09c4753f8dd83db29c48a0df988b"
+dependencies = [
+ "aho-corasick",
+ "memchr",
+]
+
+[[package]]
+name = "some-crate"
+version = "0.1.0"
+dependencies = [
+ "regex",
+ "some-internal-crate",
+]
+
+[[package]]
+name = "some-internal-crate"
+version = "0.1.0"
+"#;
+
+    #[test]
+    fn direct_dependencies() {
+        let result = CargoLock {
+            include_transitive: false,
+            ..Default::default()
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![Soup {
+                name: "regex".to_owned(),
+                version: "1.6.0".to_owned(),
+                meta: Map::new()
+            }]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test_case(vec![DependencyKind::Normal], vec![("regex", "normal")])]
+    #[test_case(vec![DependencyKind::Dev], vec![("memchr", "dev")])]
+    #[test_case(
+        vec![DependencyKind::Normal, DependencyKind::Build, DependencyKind::Dev],
+        vec![("memchr", "dev"), ("regex", "normal")]
+    )]
+    fn direct_dependencies_of_kinds(kinds: Vec<DependencyKind>, expected: Vec<(&str, &str)>) {
+        let input = r#"
+[[package]]
+name = "some-crate"
+version = "0.1.0"
+dependencies = [
+ "memchr",
+ "regex",
+]
+
+[[package]]
+name = "memchr"
+version = "2.5.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "regex"
+version = "1.6.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let manifest = r#"
+[package]
+name = "some-crate"
+
+[dependencies]
+re = { package = "regex", version = "1.6" }
+
+[dev-dependencies]
+memchr = "2.5"
+"#;
+        let result = CargoLock {
+            include_transitive: false,
+            kinds,
+            manifests: vec![manifest.to_owned()],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            expected,
+            soups
+                .iter()
+                .map(|soup| (
+                    soup.name.as_str(),
+                    soup.meta["dependency-kind"].as_str().unwrap()
+                ))
+                .collect::<Vec<(&str, &str)>>()
+        );
+    }
+
+    #[test]
+    fn transitive_dependencies() {
+        let result = CargoLock {
+            include_transitive: true,
+            ..Default::default()
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "aho-corasick".to_owned(),
+                    version: "0.7.18".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "memchr".to_owned(),
+                    version: "2.5.0".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "regex".to_owned(),
+                    version: "1.6.0".to_owned(),
+                    meta: Map::new()
+                }
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test]
+    fn multiple_versions_of_dependency() {
+        let input = r#"
+[[package]]
+name = "some-crate"
+version = "0.1.0"
+dependencies = [
+ "bitflags 1.3.2",
+]
+
+[[package]]
+name = "bitflags"
+version = "1.3.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "bitflags"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let result = CargoLock {
+            include_transitive: false,
+            ..Default::default()
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        assert_eq!(
+            true,
+            soups.contains(&Soup {
+                name: "bitflags".to_owned(),
+                version: "1.3.2".to_owned(),
+                meta: Map::new()
+            })
+        );
+    }
+
+    #[test_case("version = 3")]
+    #[test_case("")]
+    fn no_packages(input: &str) {
+        let result = CargoLock {
+            include_transitive: true,
+            ..Default::default()
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test]
+    fn invalid_lock_file() {
+        let result = CargoLock {
+            include_transitive: false,
+            ..Default::default()
+        }
+        .soups("[[package]]\nname = 4", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -1,11 +1,39 @@
 use super::SoupParse;
 use crate::soup::model::{Soup, SoupSourceParseError};
-use quick_xml::events::Event;
+use lazy_static::lazy_static;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
+use regex::{Captures, Regex};
 use serde_json::{Map, Value};
 use std::collections::{BTreeSet, HashMap};
 
-pub struct CsProj {}
+#[derive(Default)]
+pub struct CsProj {
+    /// Content of the nearest Directory.Build.props, which is imported before the project
+    pub directory_build_props: Option<String>,
+    /// Content of the nearest Directory.Packages.props, for Central Package Management
+    pub directory_packages_props: Option<String>,
+}
+
+lazy_static! {
+    static ref PROPERTY: Regex = Regex::new(r"\$\((?P<name>[A-Za-z_][A-Za-z0-9_\-]*)\)").unwrap();
+}
+
+#[derive(Default)]
+struct Project {
+    properties: Vec<(String, String)>,
+    package_references: Vec<PackageReference>,
+    package_versions: Vec<PackageReference>,
+}
+
+#[derive(Default)]
+struct PackageReference {
+    include: Option<String>,
+    update: Option<String>,
+    remove: Option<String>,
+    version: Option<String>,
+    version_override: Option<String>,
+}
 
 impl SoupParse for CsProj {
     fn soups(
@@ -13,62 +41,191 @@ impl SoupParse for CsProj {
         content: &str,
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
-        let mut reader = Reader::from_str(content);
-        reader.trim_text(true);
-        reader.expand_empty_elements(true);
+        let mut properties = HashMap::new();
+        if let Some(directory_build_props) = &self.directory_build_props {
+            define_properties(&parse(directory_build_props)?, &mut properties);
+        }
+        let mut central_versions = HashMap::new();
+        if let Some(directory_packages_props) = &self.directory_packages_props {
+            let packages_props = parse(directory_packages_props)?;
+            define_properties(&packages_props, &mut properties);
+            for package_version in packages_props.package_versions {
+                if let (Some(name), Some(version)) =
+                    (package_version.include, package_version.version)
+                {
+                    central_versions.insert(name.to_lowercase(), version);
+                }
+            }
+        }
+        let project = parse(content)?;
+        define_properties(&project, &mut properties);
 
-        let mut soups: BTreeSet<Soup> = BTreeSet::new();
-        let mut buf = Vec::new();
-        loop {
-            match reader.read_event(&mut buf) {
-                Ok(Event::Start(ref e)) => {
-                    if let b"PackageReference" = e.name() {
-                        let attributes_by_key = e
-                            .attributes()
-                            .filter_map(|attribute| attribute.ok())
-                            .map(|attribute| (attribute.key.to_vec(), attribute.value.to_vec()))
-                            .collect::<HashMap<Vec<u8>, Vec<u8>>>();
-                        let name = attribute_value(&attributes_by_key, "Include")?;
-                        let version = attribute_value(&attributes_by_key, "Version")?;
-                        soups.insert(Soup {
-                            name,
-                            version,
-                            meta: default_meta.clone(),
-                        });
+        let mut package_references: Vec<PackageReference> = Vec::new();
+        for package_reference in project.package_references {
+            if package_reference.include.is_some() {
+                package_references.push(package_reference);
+            } else if let Some(update) = &package_reference.update {
+                for item in package_references
+                    .iter_mut()
+                    .filter(|item| is_same_package(item, update))
+                {
+                    if package_reference.version.is_some() {
+                        item.version = package_reference.version.clone();
+                    }
+                    if package_reference.version_override.is_some() {
+                        item.version_override = package_reference.version_override.clone();
                     }
                 }
-                Ok(Event::Eof) => break,
-                Err(e) => {
-                    return Err(SoupSourceParseError {
-                        message: format!("Invalid XML structure {}", e),
+            } else if let Some(remove) = &package_reference.remove {
+                package_references.retain(|item| !is_same_package(item, remove));
+            } else {
+                return Err(SoupSourceParseError {
+                    message: "Missing required attribute: Include".to_owned(),
+                });
+            }
+        }
+
+        let mut soups: BTreeSet<Soup> = BTreeSet::new();
+        for package_reference in package_references {
+            let name = package_reference.include.unwrap_or_default();
+            let version = match package_reference
+                .version_override
+                .or(package_reference.version)
+                .or_else(|| central_versions.get(&name.to_lowercase()).cloned())
+            {
+                Some(version) => interpolate(&version, &properties),
+                None => "unknown".to_owned(),
+            };
+            soups.insert(Soup {
+                name,
+                version,
+                meta: default_meta.clone(),
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// `Update` and `Remove` items only apply to packages included before them in the same file.
+fn is_same_package(package_reference: &PackageReference, name: &str) -> bool {
+    match &package_reference.include {
+        Some(include) => include.eq_ignore_ascii_case(name),
+        None => false,
+    }
+}
+
+/// Properties are evaluated in order, so a property may refer to those defined before it.
+fn define_properties(project: &Project, properties: &mut HashMap<String, String>) {
+    for (name, value) in &project.properties {
+        let value = interpolate(value, properties);
+        properties.insert(name.to_owned(), value);
+    }
+}
+
+/// Replaces `$(Property)` references, leaving unknown properties as they are.
+fn interpolate(value: &str, properties: &HashMap<String, String>) -> String {
+    PROPERTY
+        .replace_all(value, |captures: &Captures| {
+            match properties.get(&captures["name"]) {
+                Some(property) => property.to_owned(),
+                None => captures[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+fn parse(content: &str) -> Result<Project, SoupSourceParseError> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+
+    let mut project = Project::default();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let items = match e.name() {
+                    b"PackageReference" => Some(&mut project.package_references),
+                    b"PackageVersion" => Some(&mut project.package_versions),
+                    _ => None,
+                };
+                if let Some(items) = items {
+                    items.push(PackageReference {
+                        include: attribute_value(e, "Include")?,
+                        update: attribute_value(e, "Update")?,
+                        remove: attribute_value(e, "Remove")?,
+                        version: attribute_value(e, "Version")?,
+                        version_override: attribute_value(e, "VersionOverride")?,
                     });
                 }
-                _ => {}
+                path.push(e.name().to_vec());
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
             }
+            Ok(Event::Text(ref e)) => {
+                let text = match e.unescape_and_decode(&reader) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        return Err(SoupSourceParseError {
+                            message: format!("Invalid XML structure {}", e),
+                        });
+                    }
+                };
+                let path = path.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>();
+                match path.as_slice() {
+                    [b"Project", b"PropertyGroup", name] => {
+                        let name = String::from_utf8_lossy(name).into_owned();
+                        project.properties.push((name, text));
+                    }
+                    [.., b"PackageReference", b"Version"] => {
+                        if let Some(package_reference) = project.package_references.last_mut() {
+                            package_reference.version = Some(text);
+                        }
+                    }
+                    [.., b"PackageVersion", b"Version"] => {
+                        if let Some(package_version) = project.package_versions.last_mut() {
+                            package_version.version = Some(text);
+                        }
+                    }
+                    [.., b"PackageReference", b"VersionOverride"] => {
+                        if let Some(package_reference) = project.package_references.last_mut() {
+                            package_reference.version_override = Some(text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid XML structure {}", e),
+                });
+            }
+            _ => {}
         }
         buf.clear();
-        Ok(soups)
     }
+    Ok(project)
 }
 
 fn attribute_value(
-    attributes: &HashMap<Vec<u8>, Vec<u8>>,
+    element: &BytesStart,
     key: &str,
-) -> Result<String, SoupSourceParseError> {
-    match attributes.get(key.as_bytes()) {
-        Some(value) => match String::from_utf8(value.to_owned()) {
-            Ok(value) => Ok(value),
-            Err(_e) => {
-                return Err(SoupSourceParseError {
-                    message: format!("Unable to parse attribute {} as utf8", key),
-                });
-            }
+) -> Result<Option<String>, SoupSourceParseError> {
+    let attribute = element
+        .attributes()
+        .filter_map(|attribute| attribute.ok())
+        .find(|attribute| attribute.key == key.as_bytes());
+    match attribute {
+        Some(attribute) => match String::from_utf8(attribute.value.to_vec()) {
+            Ok(value) => Ok(Some(value)),
+            Err(_e) => Err(SoupSourceParseError {
+                message: format!("Unable to parse attribute {} as utf8", key),
+            }),
         },
-        None => {
-            return Err(SoupSourceParseError {
-                message: format!("Missing required attribute: {}", key),
-            })
-        }
+        None => Ok(None),
     }
 }
 
@@ -86,7 +243,7 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -109,7 +266,7 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -130,6 +287,194 @@ mod tests {
         assert_eq!(expected_soups, soups);
     }
 
+    #[test]
+    fn version_element_and_override() {
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk">
+    <ItemGroup>
+        <PackageReference Include="Serilog">
+            <Version>2.12.0</Version>
+        </PackageReference>
+        <PackageReference Include="Newtonsoft.Json" Version="13.0.1" VersionOverride="12.0.3" />
+        <PackageReference Include="Polly" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let expected_soups = vec![
+            Soup {
+                name: "Newtonsoft.Json".to_owned(),
+                version: "12.0.3".to_owned(),
+                meta: Map::new(),
+            },
+            Soup {
+                name: "Polly".to_owned(),
+                version: "unknown".to_owned(),
+                meta: Map::new(),
+            },
+            Soup {
+                name: "Serilog".to_owned(),
+                version: "2.12.0".to_owned(),
+                meta: Map::new(),
+            },
+        ]
+        .into_iter()
+        .collect::<BTreeSet<Soup>>();
+        assert_eq!(expected_soups, result.unwrap());
+    }
+
+    #[test]
+    fn property_versions() {
+        let directory_build_props = r#"
+<Project>
+    <PropertyGroup>
+        <AzureSdkVersion>7.2.1</AzureSdkVersion>
+        <SerilogVersion>2.11.0</SerilogVersion>
+    </PropertyGroup>
+</Project>
+        "#;
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk">
+    <PropertyGroup>
+        <SerilogVersion>2.12.0</SerilogVersion>
+        <SinkVersion>$(SerilogVersion)</SinkVersion>
+    </PropertyGroup>
+    <ItemGroup>
+        <PackageReference Include="Azure.Messaging.ServiceBus" Version="$(AzureSdkVersion)" />
+        <PackageReference Include="Serilog" Version="$(SerilogVersion)" />
+        <PackageReference Include="Serilog.Sinks.Console" Version="$(SinkVersion)" />
+        <PackageReference Include="Polly" Version="$(PollyVersion)" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj {
+            directory_build_props: Some(directory_build_props.to_owned()),
+            ..CsProj::default()
+        }
+        .soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("Azure.Messaging.ServiceBus".to_owned(), "7.2.1".to_owned()),
+                ("Polly".to_owned(), "$(PollyVersion)".to_owned()),
+                ("Serilog".to_owned(), "2.12.0".to_owned()),
+                ("Serilog.Sinks.Console".to_owned(), "2.12.0".to_owned()),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn central_package_management() {
+        let directory_packages_props = r#"
+<Project>
+    <PropertyGroup>
+        <ManagePackageVersionsCentrally>true</ManagePackageVersionsCentrally>
+        <SerilogVersion>2.12.0</SerilogVersion>
+    </PropertyGroup>
+    <ItemGroup>
+        <PackageVersion Include="Newtonsoft.Json" Version="13.0.1" />
+        <PackageVersion Include="Serilog" Version="$(SerilogVersion)" />
+        <PackageVersion Include="Polly">
+            <Version>7.2.3</Version>
+        </PackageVersion>
+    </ItemGroup>
+</Project>
+        "#;
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk">
+    <ItemGroup>
+        <PackageReference Include="newtonsoft.json" />
+        <PackageReference Include="Serilog" />
+        <PackageReference Include="Polly" VersionOverride="7.1.0" />
+        <PackageReference Include="Dapper" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj {
+            directory_packages_props: Some(directory_packages_props.to_owned()),
+            ..CsProj::default()
+        }
+        .soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("Dapper".to_owned(), "unknown".to_owned()),
+                ("Polly".to_owned(), "7.1.0".to_owned()),
+                ("Serilog".to_owned(), "2.12.0".to_owned()),
+                ("newtonsoft.json".to_owned(), "13.0.1".to_owned()),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn update_and_remove_items() {
+        let content = r#"
+<Project>
+    <ItemGroup>
+        <PackageReference Include="Serilog" Version="2.11.0" />
+        <PackageReference Include="Polly" Version="7.2.3" />
+        <PackageReference Update="serilog" Version="2.12.0" />
+        <PackageReference Update="Newtonsoft.Json" Version="13.0.1" />
+        <PackageReference Remove="Polly" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(vec![("Serilog".to_owned(), "2.12.0".to_owned())], versions);
+    }
+
+    #[test]
+    fn directory_build_props_with_update_items() {
+        let content = r#"
+<Project>
+    <ItemGroup>
+        <PackageReference Update="Newtonsoft.Json" Version="13.0.1" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test]
+    fn missing_include() {
+        let content = r#"
+<Project>
+    <ItemGroup>
+        <PackageReference Version="13.0.1" />
+    </ItemGroup>
+</Project>
+        "#;
+
+        let result = CsProj::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
     #[test]
     fn no_dependencies() {
         let content = r#"
@@ -139,7 +484,7 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
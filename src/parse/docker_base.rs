@@ -1,24 +1,28 @@
 use super::SoupParse;
 use crate::soup::model::{Soup, SoupSourceParseError};
 use lazy_static::lazy_static;
-use regex::{Regex, RegexSet};
+use regex::Regex;
 use serde_json::{Map, Value};
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
 pub struct DockerBase {}
 
-static PATTERNS: [&str; 2] = [
-    r"^(?i)FROM(?-i) (?:--platform=[\w/]+ )?(?P<name>(?:[a-z0-9\.\-_]+){1}(?:/[a-z0-9\.\-_]+)*)[:@](?P<tag>[a-zA-Z0-9\.\-_]+)(?: (?i)AS(?-i) [\w\-]+)?$",
-    r"^(?i)FROM(?-i) (?:--platform=[\w/]+ )?(?P<name>(?:[a-z0-9\.\-_]+){1}:[0-9]+(?:/[a-z0-9\.\-_]+)*)[:@](?P<tag>[a-zA-Z0-9\.\-_]+)(?: (?i)AS(?-i) [\w\-]+)?$",
-];
+pub const UNPINNED_VERSION: &str = "latest (unpinned)";
+
+const DEFAULT_REGISTRY: &str = "docker.io";
+
+struct ImageReference<'a> {
+    registry: Option<&'a str>,
+    repository: &'a str,
+    tag: Option<&'a str>,
+    digest: Option<&'a str>,
+}
 
 lazy_static! {
-    static ref PATTERN_SET: RegexSet = RegexSet::new(&PATTERNS).unwrap();
-    static ref REGEXES: Vec<Regex> = PATTERN_SET
-        .patterns()
-        .iter()
-        .map(|pat| Regex::new(pat).unwrap())
-        .collect();
+    static ref FROM: Regex = Regex::new(
+        r"^\s*(?i)FROM(?-i)\s+(?:--platform=\S+\s+)?(?P<image>\S+)(?:\s+(?i)AS(?-i)\s+(?P<alias>\S+))?\s*$"
+    )
+    .unwrap();
 }
 
 impl SoupParse for DockerBase {
@@ -28,33 +32,89 @@ impl SoupParse for DockerBase {
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
         let mut result: BTreeSet<Soup> = BTreeSet::new();
-        let lines = content.lines();
-        for line in lines {
-            let matching_patterns = PATTERN_SET
-                .matches(line)
-                .into_iter()
-                .map(|match_id| &REGEXES[match_id])
-                .collect::<Vec<&Regex>>();
-            if let Some(pattern) = matching_patterns.first() {
-                if let Some(captures) = pattern.captures(line) {
-                    result.insert(Soup {
-                        name: named_capture(&captures, "name")?,
-                        version: named_capture(&captures, "tag")?,
-                        meta: default_meta.clone(),
-                    });
-                }
+        let mut stages: HashSet<String> = HashSet::new();
+        for line in content.lines() {
+            let captures = match FROM.captures(line) {
+                Some(captures) => captures,
+                None => continue,
+            };
+            let image = &captures["image"];
+            let is_stage = stages.contains(&image.to_lowercase());
+            if let Some(alias) = captures.name("alias") {
+                stages.insert(alias.as_str().to_lowercase());
+            }
+            // Unresolved variables, such as an ARG without a default, leave no usable image name
+            if is_stage || image == "scratch" || image.contains('$') {
+                continue;
             }
+            result.insert(image_soup(image, default_meta));
         }
         Ok(result)
     }
 }
 
-fn named_capture(captures: &regex::Captures, name: &str) -> Result<String, SoupSourceParseError> {
-    match captures.name(name) {
-        Some(value) => Ok(value.as_str().to_owned()),
-        None => Err(SoupSourceParseError {
-            message: "Unable to parse FROM statement in dockerfile".to_owned(),
-        }),
+/// Creates a SOUP for an image reference such as `registry:5000/repository:tag@sha256:digest`.
+/// The version is the tag, the digest or both, and images without either are reported as
+/// unpinned. Each part of the reference is also recorded as a meta key.
+pub fn image_soup(image: &str, default_meta: &Map<String, Value>) -> Soup {
+    let reference = parse_reference(image);
+    let version = match (reference.tag, reference.digest) {
+        (Some(tag), Some(digest)) => format!("{}@{}", tag, digest),
+        (Some(version), None) | (None, Some(version)) => version.to_owned(),
+        (None, None) => UNPINNED_VERSION.to_owned(),
+    };
+    let name = match reference.registry {
+        Some(registry) => format!("{}/{}", registry, reference.repository),
+        None => reference.repository.to_owned(),
+    };
+    let mut meta = default_meta.clone();
+    let parts = [
+        (
+            "registry",
+            Some(reference.registry.unwrap_or(DEFAULT_REGISTRY)),
+        ),
+        ("repository", Some(reference.repository)),
+        ("tag", reference.tag),
+        ("digest", reference.digest),
+    ];
+    for (key, value) in parts {
+        if let Some(value) = value {
+            meta.insert(key.to_owned(), Value::from(value));
+        }
+    }
+    Soup {
+        name,
+        version,
+        meta,
+    }
+}
+
+/// The first part of the name is a registry when it looks like a host name, as decided by
+/// Docker: it contains a `.` or a `:`, or is `localhost`.
+fn parse_reference(image: &str) -> ImageReference<'_> {
+    let (image, digest) = match image.split_once('@') {
+        Some((image, digest)) => (image, Some(digest)),
+        None => (image, None),
+    };
+    let name_start = image.rfind('/').map(|index| index + 1).unwrap_or(0);
+    let (name, tag) = match image[name_start..].rfind(':') {
+        Some(index) => (
+            &image[..name_start + index],
+            Some(&image[name_start + index + 1..]).filter(|tag| !tag.is_empty()),
+        ),
+        None => (image, None),
+    };
+    let (registry, repository) = match name.split_once('/') {
+        Some((host, repository)) if host.contains(['.', ':']) || host == "localhost" => {
+            (Some(host), repository)
+        }
+        _ => (None, name),
+    };
+    ImageReference {
+        registry,
+        repository,
+        tag,
+        digest,
     }
 }
 
@@ -201,4 +261,117 @@ mod tests {
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
     }
+
+    #[test]
+    fn stage_aliases() {
+        let input = r#"FROM mcr.microsoft.com/dotnet/sdk:6.0 AS build-env
+RUN dotnet publish -c Release -o out
+FROM build-env AS test
+RUN dotnet test
+FROM mcr.microsoft.com/dotnet/aspnet:6.0
+COPY --from=build-env /app/out ."#;
+        let result = DockerBase {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "mcr.microsoft.com/dotnet/aspnet",
+                "mcr.microsoft.com/dotnet/sdk"
+            ],
+            names
+        );
+    }
+
+    #[test_case("FROM scratch")]
+    #[test_case("FROM golang:1.19 AS builder\nFROM scratch\nFROM BUILDER")]
+    fn scratch(input: &str) {
+        let result = DockerBase {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            true,
+            result.unwrap().iter().all(|soup| soup.name == "golang")
+        );
+    }
+
+    #[test_case("FROM ${BASE}")]
+    #[test_case("FROM $BASE AS build\nFROM build")]
+    #[test_case("FROM golang:${GO_VERSION}\nFROM ${REGISTRY}/golang:1.19")]
+    fn unresolved_variables(input: &str) {
+        let result = DockerBase {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test_case("FROM ubuntu", "ubuntu")]
+    #[test_case("FROM --platform=linux/amd64 ubuntu AS base", "ubuntu")]
+    #[test_case(
+        "FROM mcr.microsoft.com:443/dotnet/sdk",
+        "mcr.microsoft.com:443/dotnet/sdk"
+    )]
+    fn untagged(input: &str, expected_name: &str) {
+        let result = DockerBase {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!(
+            soup,
+            Soup {
+                name: expected_name.to_owned(),
+                version: UNPINNED_VERSION.to_owned(),
+                meta: Map::new()
+            }
+        );
+    }
+
+    #[test_case(
+        "FROM mcr.microsoft.com/dotnet/sdk:6.0@sha256:ca468b84b84846e84",
+        "mcr.microsoft.com/dotnet/sdk",
+        "6.0@sha256:ca468b84b84846e84"
+    )]
+    #[test_case(
+        "FROM localhost:5000/fedora/httpd@sha256:ca468b84b84846e84",
+        "localhost:5000/fedora/httpd",
+        "sha256:ca468b84b84846e84"
+    )]
+    fn tag_and_digest(input: &str, expected_name: &str, expected_version: &str) {
+        let result = DockerBase {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soup = result.unwrap().into_iter().next().unwrap();
+        assert_eq!(expected_name, soup.name);
+        assert_eq!(expected_version, soup.version);
+    }
+
+    #[test_case(
+        "mcr.microsoft.com:443/dotnet/sdk:6.0@sha256:ca468b84b84846e84",
+        "mcr.microsoft.com:443",
+        "dotnet/sdk",
+        Some("6.0"),
+        Some("sha256:ca468b84b84846e84")
+    )]
+    #[test_case(
+        "fedora/httpd:v1.6.2",
+        "docker.io",
+        "fedora/httpd",
+        Some("v1.6.2"),
+        None
+    )]
+    #[test_case("postgres", "docker.io", "postgres", None, None)]
+    fn reference_meta(
+        image: &str,
+        registry: &str,
+        repository: &str,
+        tag: Option<&str>,
+        digest: Option<&str>,
+    ) {
+        let soup = image_soup(image, &Map::new());
+        assert_eq!(Some(&Value::from(registry)), soup.meta.get("registry"));
+        assert_eq!(Some(&Value::from(repository)), soup.meta.get("repository"));
+        assert_eq!(tag.map(Value::from).as_ref(), soup.meta.get("tag"));
+        assert_eq!(digest.map(Value::from).as_ref(), soup.meta.get("digest"));
+    }
 }
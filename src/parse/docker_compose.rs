@@ -0,0 +1,208 @@
+use super::{docker_base, dockerfile, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct DockerCompose {}
+
+#[derive(Deserialize)]
+struct Content {
+    services: Option<HashMap<String, Service>>,
+}
+
+#[derive(Deserialize)]
+struct Service {
+    image: Option<String>,
+    build: Option<serde_yaml::Value>,
+}
+
+impl SoupParse for DockerCompose {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut content: serde_yaml::Value = match serde_yaml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid compose file structure ({})", e),
+                })
+            }
+        };
+        apply_merge_keys(&mut content);
+        let content: Content = match serde_yaml::from_value(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid compose file structure ({})", e),
+                })
+            }
+        };
+        let soups = content
+            .services
+            .unwrap_or_default()
+            .into_values()
+            // The image of a service that is built names the result of the build
+            .filter(|service| service.build.is_none())
+            .filter_map(|service| service.image)
+            // Variables come from the environment when running Compose, which is not known here
+            .map(|image| dockerfile::substitute_unset_as_empty(&image, &HashMap::new()))
+            .filter(|image| !image.is_empty())
+            .map(|image| docker_base::image_soup(&image, default_meta))
+            .collect();
+        Ok(soups)
+    }
+}
+
+/// Applies YAML merge keys (`<<: *base`), which are not resolved by the YAML parser. Keys
+/// of the mapping itself take precedence over merged ones, and earlier merged mappings over
+/// later ones.
+fn apply_merge_keys(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            if let Some(merged) = mapping.remove(&serde_yaml::Value::from("<<")) {
+                let merged = match merged {
+                    serde_yaml::Value::Sequence(sequence) => sequence,
+                    merged => vec![merged],
+                };
+                for mut merged in merged {
+                    apply_merge_keys(&mut merged);
+                    if let serde_yaml::Value::Mapping(merged) = merged {
+                        for (key, value) in merged {
+                            if !mapping.contains_key(&key) {
+                                mapping.insert(key, value);
+                            }
+                        }
+                    }
+                }
+            }
+            for (_, value) in mapping.iter_mut() {
+                apply_merge_keys(value);
+            }
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            for value in sequence {
+                apply_merge_keys(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn service_images() {
+        let content = r#"
+version: "3.9"
+services:
+  app:
+    build: .
+    image: some-app:latest
+  db:
+    image: postgres:14.4
+  broker:
+    image: "rabbitmq:3.11-management@sha256:ca468b84b84846e84"
+  cache:
+    image: redis
+"#;
+        let result = DockerCompose {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("postgres".to_owned(), "14.4".to_owned()),
+                (
+                    "rabbitmq".to_owned(),
+                    "3.11-management@sha256:ca468b84b84846e84".to_owned()
+                ),
+                ("redis".to_owned(), docker_base::UNPINNED_VERSION.to_owned()),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn interpolated_images() {
+        let content = r#"
+services:
+  web:
+    image: nginx:${NGINX_VERSION:-1.23}
+  db:
+    image: "postgres:${POSTGRES_VERSION-14.4}"
+  cache:
+    image: redis:${REDIS_VERSION}
+"#;
+        let result = DockerCompose {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("nginx".to_owned(), "1.23".to_owned()),
+                ("postgres".to_owned(), "14.4".to_owned()),
+                ("redis".to_owned(), docker_base::UNPINNED_VERSION.to_owned()),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn merge_keys() {
+        let content = r#"
+x-base: &base
+  image: postgres:14.4
+  restart: always
+x-built: &built
+  build: .
+services:
+  primary:
+    <<: *base
+  replica:
+    <<: [*base]
+    image: postgres:15.1
+  app:
+    <<: [*built, *base]
+"#;
+        let result = DockerCompose {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("postgres".to_owned(), "14.4".to_owned()),
+                ("postgres".to_owned(), "15.1".to_owned()),
+            ],
+            versions
+        );
+    }
+
+    #[test_case("services: {}")]
+    #[test_case("version: \"3.9\"")]
+    fn no_services(input: &str) {
+        let result = DockerCompose {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        let result = DockerCompose {}.soups("services: [", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use super::{shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Resolves `ARG` and `ENV` variables in a Dockerfile before handing it to the given parsers.
+pub struct Dockerfile {
+    /// Values given with `--build-arg`, overriding the defaults of declared `ARG`s
+    pub build_args: HashMap<String, String>,
+    pub parsers: Vec<Box<dyn SoupParse>>,
+}
+
+lazy_static! {
+    static ref INSTRUCTION: Regex = Regex::new(r"^\s*(?P<instruction>[A-Za-z]+)\s+(?P<arguments>.*)$").unwrap();
+    static ref VARIABLE: Regex = Regex::new(
+        r"\$(?:\{(?P<braced>[A-Za-z_][A-Za-z0-9_]*)(?:(?P<colon>:)?(?P<operator>[-+])(?P<word>[^}]*))?\}|(?P<plain>[A-Za-z_][A-Za-z0-9_]*))"
+    )
+    .unwrap();
+}
+
+impl SoupParse for Dockerfile {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content = resolve_variables(content, &self.build_args);
+        let mut soups = BTreeSet::new();
+        for parser in &self.parsers {
+            soups.extend(parser.soups(&content, default_meta)?);
+        }
+        Ok(soups)
+    }
+}
+
+/// Global `ARG`s declared before the first `FROM` are only available in `FROM` instructions,
+/// unless redeclared without a value inside a stage. Each stage starts with no variables.
+fn resolve_variables(content: &str, build_args: &HashMap<String, String>) -> String {
+    let mut global_args: HashMap<String, String> = HashMap::new();
+    let mut stage_variables: Option<HashMap<String, String>> = None;
+    let mut lines = Vec::new();
+    for line in shell::normalize(content).lines() {
+        let captures = match INSTRUCTION.captures(line) {
+            Some(captures) if !line.trim_start().starts_with('#') => captures,
+            _ => {
+                lines.push(line.to_owned());
+                continue;
+            }
+        };
+        let instruction = captures["instruction"].to_uppercase();
+        let arguments = &captures["arguments"];
+        if instruction == "FROM" {
+            lines.push(substitute(line, &global_args));
+            stage_variables = Some(HashMap::new());
+            continue;
+        }
+        let line = substitute(line, stage_variables.as_ref().unwrap_or(&global_args));
+        match instruction.as_str() {
+            "ARG" => {
+                for (name, default) in arg_declarations(arguments) {
+                    let variables = stage_variables.as_ref().unwrap_or(&global_args);
+                    let value = match (build_args.get(&name), default, &stage_variables) {
+                        (Some(value), _, _) => Some(value.to_owned()),
+                        (None, Some(default), _) => Some(substitute(&default, variables)),
+                        (None, None, Some(_)) => global_args.get(&name).cloned(),
+                        (None, None, None) => None,
+                    };
+                    // A variable set with ENV takes precedence over an ARG of the same name
+                    if let Some(value) = value {
+                        stage_variables
+                            .as_mut()
+                            .unwrap_or(&mut global_args)
+                            .entry(name)
+                            .or_insert(value);
+                    }
+                }
+            }
+            "ENV" => {
+                for (name, value) in env_declarations(arguments) {
+                    let variables = stage_variables.as_mut().unwrap_or(&mut global_args);
+                    let value = substitute(&value, variables);
+                    variables.insert(name, value);
+                }
+            }
+            _ => {}
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Replaces `$NAME`, `${NAME}`, `${NAME:-default}` and `${NAME:+alternative}`, as well as the
+/// `${NAME-default}` and `${NAME+alternative}` forms that only check whether a variable is set.
+/// Unknown variables are left as they are, since they may be set by the shell in `RUN`
+/// instructions.
+fn substitute(value: &str, variables: &HashMap<String, String>) -> String {
+    replace_variables(value, variables, false)
+}
+
+/// Replaces variables like in a Dockerfile, but with unknown variables left empty, as Docker
+/// Compose does.
+pub fn substitute_unset_as_empty(value: &str, variables: &HashMap<String, String>) -> String {
+    replace_variables(value, variables, true)
+}
+
+fn replace_variables(
+    value: &str,
+    variables: &HashMap<String, String>,
+    unset_as_empty: bool,
+) -> String {
+    VARIABLE
+        .replace_all(value, |captures: &Captures| {
+            let name = match (captures.name("braced"), captures.name("plain")) {
+                (Some(name), _) | (None, Some(name)) => name.as_str(),
+                (None, None) => return captures[0].to_owned(),
+            };
+            let variable = variables.get(name);
+            let is_set = match (variable, captures.name("colon")) {
+                (Some(value), Some(_)) => !value.is_empty(),
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            match (
+                captures.name("operator").map(|operator| operator.as_str()),
+                variable,
+            ) {
+                (Some("+"), _) if is_set => captures["word"].to_owned(),
+                (Some("+"), _) => String::new(),
+                (Some(_), Some(variable)) if is_set => variable.to_owned(),
+                (Some(_), _) => captures["word"].to_owned(),
+                (None, Some(variable)) => variable.to_owned(),
+                (None, None) if unset_as_empty => String::new(),
+                (None, None) => captures[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+fn arg_declarations(arguments: &str) -> Vec<(String, Option<String>)> {
+    shell::commands(arguments)
+        .into_iter()
+        .flatten()
+        .map(|word| match word.split_once('=') {
+            Some((name, default)) => (name.to_owned(), Some(default.to_owned())),
+            None => (word, None),
+        })
+        .collect()
+}
+
+/// Supports both `ENV NAME=value ...` and the legacy `ENV NAME value` form.
+fn env_declarations(arguments: &str) -> Vec<(String, String)> {
+    let words = shell::commands(arguments)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>();
+    match words.first() {
+        Some(first) if !first.contains('=') => {
+            let value = arguments
+                .trim_start()
+                .strip_prefix(first.as_str())
+                .unwrap_or_default()
+                .trim();
+            vec![(first.to_owned(), value.trim_matches('"').to_owned())]
+        }
+        _ => words
+            .into_iter()
+            .filter_map(|word| {
+                let (name, value) = word.split_once('=')?;
+                Some((name.to_owned(), value.to_owned()))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn global_args_in_from() {
+        let input = "ARG NODE_VERSION=16.17\nARG VARIANT\nFROM node:${NODE_VERSION}-alpine\nRUN echo $NODE_VERSION";
+        assert_eq!(
+            "ARG NODE_VERSION=16.17\nARG VARIANT\nFROM node:16.17-alpine\nRUN echo $NODE_VERSION",
+            resolve_variables(input, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn stage_args_and_env() {
+        let input = r#"ARG CURL_VERSION=7.81.0
+FROM ubuntu:22.04 AS build
+ARG CURL_VERSION
+ENV GIT_VERSION="1:2.34.1" \
+    JQ_VERSION=1.6-2
+RUN apt-get install -y curl=${CURL_VERSION} git=$GIT_VERSION jq=${JQ_VERSION}
+FROM ubuntu:22.04
+RUN apt-get install -y curl=${CURL_VERSION:-unknown}"#;
+        let resolved = resolve_variables(input, &HashMap::new());
+        let lines = resolved.lines().collect::<Vec<&str>>();
+        assert_eq!(
+            "RUN apt-get install -y curl=7.81.0 git=1:2.34.1 jq=1.6-2",
+            lines[4]
+        );
+        assert_eq!("RUN apt-get install -y curl=unknown", lines[6]);
+    }
+
+    #[test_case("ARG VERSION=1.0\nFROM alpine:${VERSION}", "FROM alpine:2.0")]
+    #[test_case(
+        "FROM alpine:3.16\nARG VERSION=1.0\nRUN apk add curl=$VERSION",
+        "RUN apk add curl=2.0"
+    )]
+    #[test_case(
+        "FROM alpine:3.16\nARG OTHER=1.0\nRUN apk add curl=$VERSION",
+        "RUN apk add curl=$VERSION"
+    )]
+    fn build_args(input: &str, expected_line: &str) {
+        let build_args = vec![("VERSION".to_owned(), "2.0".to_owned())]
+            .into_iter()
+            .collect::<HashMap<String, String>>();
+        let resolved = resolve_variables(input, &build_args);
+        assert_eq!(Some(expected_line), resolved.lines().last());
+    }
+
+    #[test_case("${SET:-default}", "value")]
+    #[test_case("${EMPTY:-default}", "default")]
+    #[test_case("${EMPTY-default}", "")]
+    #[test_case("${UNSET-default}", "default")]
+    #[test_case("${EMPTY:+alternative}", "")]
+    #[test_case("${EMPTY+alternative}", "alternative")]
+    #[test_case("${UNSET}", "${UNSET}")]
+    fn substitution(input: &str, expected: &str) {
+        let variables = vec![
+            ("SET".to_owned(), "value".to_owned()),
+            ("EMPTY".to_owned(), String::new()),
+        ]
+        .into_iter()
+        .collect::<HashMap<String, String>>();
+        assert_eq!(expected, substitute(input, &variables));
+    }
+
+    #[test]
+    fn unset_as_empty() {
+        assert_eq!(
+            "nginx:1.23-",
+            substitute_unset_as_empty("nginx:${VERSION:-1.23}-$VARIANT", &HashMap::new())
+        );
+    }
+
+    #[test_case("ENV NAME value with spaces", vec![("NAME", "value with spaces")])]
+    #[test_case("ENV A=1 B=\"two words\"", vec![("A", "1"), ("B", "two words")])]
+    fn env(input: &str, expected: Vec<(&str, &str)>) {
+        let expected = expected
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            expected,
+            env_declarations(input.strip_prefix("ENV ").unwrap())
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use super::{shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Ruby gems installed in Dockerfiles.
+pub struct Gem {}
+
+const VERSION_OPTIONS: [&str; 2] = ["-v", "--version"];
+
+const OPTIONS_WITH_VALUE: [&str; 11] = [
+    "-v",
+    "--version",
+    "-i",
+    "--install-dir",
+    "-n",
+    "--bindir",
+    "-s",
+    "--source",
+    "-P",
+    "--trust-policy",
+    "--platform",
+];
+
+impl SoupParse for Gem {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        let content = shell::normalize(content);
+        for line in content.lines() {
+            for command in shell::commands(line) {
+                let gems = match shell::subcommand_arguments(
+                    &command,
+                    &["gem"],
+                    &["install"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(gems) => gems,
+                    None => continue,
+                };
+                let version_option = version_option(&command);
+                for gem in gems {
+                    if gem.starts_with('$') || gem.ends_with(".gem") {
+                        continue;
+                    }
+                    let (name, version) = match (gem.split_once(':'), version_option) {
+                        (Some((name, version)), _) => (name, version),
+                        (None, Some(version)) => (gem, version),
+                        (None, None) => (gem, "unknown"),
+                    };
+                    result.insert(Soup {
+                        name: name.to_owned(),
+                        version: version.to_owned(),
+                        meta: default_meta.clone(),
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The version given with `-v` or `--version`, which applies to the gems of the command.
+fn version_option(command: &[String]) -> Option<&str> {
+    command.iter().enumerate().find_map(|(index, word)| {
+        if let Some(version) = word.strip_prefix("--version=") {
+            return Some(version);
+        }
+        match VERSION_OPTIONS.contains(&word.as_str()) {
+            true => command.get(index + 1).map(String::as_str),
+            false => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test_case("gem install bundler -v 2.3.26", "2.3.26")]
+    #[test_case("gem install bundler --version '~> 2.3'", "~> 2.3")]
+    #[test_case("gem install bundler --version=2.3.26 --no-document", "2.3.26")]
+    #[test_case("gem install --no-document bundler:2.3.26", "2.3.26")]
+    #[test_case("gem install bundler", "unknown")]
+    fn versions(input: &str, expected_version: &str) {
+        let result = Gem {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("bundler", expected_version)]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn multiple_gems() {
+        let input =
+            "RUN gem install -N \\\n    rake:13.0.6 \\\n    bundler:2.3.26 \\\n && bundle install";
+        let result = Gem {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("bundler", "2.3.26"), soup("rake", "13.0.6")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct GoMod {}
+
+pub struct Module {
+    pub path: String,
+    pub version: String,
+    pub indirect: bool,
+    pub replaced_by: Option<String>,
+}
+
+struct Replacement {
+    path: String,
+    version: Option<String>,
+    new_path: String,
+    new_version: Option<String>,
+}
+
+impl SoupParse for GoMod {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let soups = modules(content)?
+            .into_iter()
+            .map(|module| {
+                let mut meta = default_meta.clone();
+                let kind = match module.indirect {
+                    true => "indirect",
+                    false => "direct",
+                };
+                meta.insert("dependency-kind".to_owned(), Value::from(kind));
+                if let Some(replaced_by) = module.replaced_by {
+                    meta.insert("replaced-by".to_owned(), Value::from(replaced_by));
+                }
+                Soup {
+                    name: module.path,
+                    version: module.version,
+                    meta,
+                }
+            })
+            .collect();
+        Ok(soups)
+    }
+}
+
+/// Returns the required modules with `exclude` and `replace` directives applied. Modules
+/// replaced by a local directory are left out.
+pub fn modules(content: &str) -> Result<Vec<Module>, SoupSourceParseError> {
+    let mut requirements = Vec::new();
+    let mut exclusions = Vec::new();
+    let mut replacements = Vec::new();
+    let mut block: Option<String> = None;
+    for line in content.lines() {
+        let (line, comment) = match line.split_once("//") {
+            Some((line, comment)) => (line, comment.trim()),
+            None => (line, ""),
+        };
+        let mut tokens = line
+            .split_whitespace()
+            .map(|token| token.trim_matches('"'))
+            .collect::<Vec<&str>>();
+        if tokens.is_empty() {
+            continue;
+        }
+        if tokens == [")"] {
+            block = None;
+            continue;
+        }
+        if tokens.len() == 2 && tokens[1] == "(" {
+            block = Some(tokens[0].to_owned());
+            continue;
+        }
+        let directive = match &block {
+            Some(directive) => directive.as_str(),
+            None => tokens.remove(0),
+        };
+        match directive {
+            "require" => {
+                let (path, version) = module_version(&tokens, line)?;
+                let indirect = comment == "indirect" || comment.starts_with("indirect;");
+                requirements.push((path, version, indirect));
+            }
+            "exclude" => exclusions.push(module_version(&tokens, line)?),
+            "replace" => replacements.push(replacement(&tokens, line)?),
+            _ => {}
+        }
+    }
+
+    let mut modules = Vec::new();
+    for (path, version, indirect) in requirements {
+        if exclusions.contains(&(path.to_owned(), version.to_owned())) {
+            continue;
+        }
+        let replacement = replacements.iter().find(|replacement| {
+            replacement.path == path
+                && match &replacement.version {
+                    Some(replaced_version) => *replaced_version == version,
+                    None => true,
+                }
+        });
+        let module = match replacement {
+            Some(Replacement {
+                new_path,
+                new_version: Some(new_version),
+                ..
+            }) => Module {
+                path,
+                version: new_version.to_owned(),
+                indirect,
+                replaced_by: Some(new_path.to_owned()),
+            },
+            Some(_) => continue,
+            None => Module {
+                path,
+                version,
+                indirect,
+                replaced_by: None,
+            },
+        };
+        modules.push(module);
+    }
+    Ok(modules)
+}
+
+fn module_version(tokens: &[&str], line: &str) -> Result<(String, String), SoupSourceParseError> {
+    match tokens {
+        [path, version] => Ok((path.to_string(), version.to_string())),
+        _ => Err(SoupSourceParseError {
+            message: format!("Invalid module version: {}", line.trim()),
+        }),
+    }
+}
+
+fn replacement(tokens: &[&str], line: &str) -> Result<Replacement, SoupSourceParseError> {
+    let (path, version, new_path, new_version) = match tokens {
+        [path, "=>", new_path] => (path, None, new_path, None),
+        [path, "=>", new_path, new_version] => (path, None, new_path, Some(new_version)),
+        [path, version, "=>", new_path] => (path, Some(version), new_path, None),
+        [path, version, "=>", new_path, new_version] => {
+            (path, Some(version), new_path, Some(new_version))
+        }
+        _ => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid replace directive: {}", line.trim()),
+            })
+        }
+    };
+    Ok(Replacement {
+        path: path.to_string(),
+        version: version.map(|version| version.to_string()),
+        new_path: new_path.to_string(),
+        new_version: new_version.map(|version| version.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    const GO_MOD: &str = r#"module example.com/gateway
+
+go 1.19
+
+require github.com/sirupsen/logrus v1.9.0
+
+require (
+	github.com/eclipse/paho.mqtt.golang v1.4.2
+	github.com/gorilla/websocket v1.4.2 // indirect
+	golang.org/x/net v0.0.0-20200425230154-ff2c4b7c35a0 // indirect
+	golang.org/x/sync v0.1.0 // indirect
+	example.com/internal v0.0.0
+)
+
+exclude golang.org/x/sync v0.1.0
+
+replace (
+	golang.org/x/net => golang.org/x/net v0.7.0
+	example.com/internal => ../internal
+)
+"#;
+
+    #[test]
+    fn required_modules() {
+        let result = GoMod {}.soups(GO_MOD, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("github.com/eclipse/paho.mqtt.golang", "v1.4.2"),
+                soup("github.com/gorilla/websocket", "v1.4.2"),
+                soup("github.com/sirupsen/logrus", "v1.9.0"),
+                soup("golang.org/x/net", "v0.7.0"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn module_meta() {
+        let soups = GoMod {}.soups(GO_MOD, &Map::new()).unwrap();
+        let meta = |name: &str, key: &str| {
+            soups
+                .iter()
+                .find(|soup| soup.name == name)
+                .and_then(|soup| soup.meta.get(key))
+                .cloned()
+        };
+        assert_eq!(
+            Some(Value::from("direct")),
+            meta("github.com/sirupsen/logrus", "dependency-kind")
+        );
+        assert_eq!(
+            Some(Value::from("indirect")),
+            meta("github.com/gorilla/websocket", "dependency-kind")
+        );
+        assert_eq!(
+            Some(Value::from("golang.org/x/net")),
+            meta("golang.org/x/net", "replaced-by")
+        );
+        assert_eq!(None, meta("github.com/sirupsen/logrus", "replaced-by"));
+    }
+
+    #[test_case("module example.com/gateway\n\ngo 1.19\n")]
+    #[test_case("")]
+    fn no_modules(input: &str) {
+        let result = GoMod {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test_case("require github.com/sirupsen/logrus")]
+    #[test_case("replace github.com/sirupsen/logrus v1.9.0")]
+    fn invalid_directives(input: &str) {
+        let result = GoMod {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use super::{go_mod, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashSet};
+
+pub struct GoSum {
+    pub include_transitive: bool,
+    pub go_mod: Option<String>,
+}
+
+impl SoupParse for GoSum {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let direct_modules = match (&self.go_mod, self.include_transitive) {
+            (Some(go_mod), false) => Some(
+                go_mod::modules(go_mod)?
+                    .into_iter()
+                    .filter(|module| !module.indirect)
+                    .map(|module| (module.replaced_by.unwrap_or(module.path), module.version))
+                    .collect::<HashSet<(String, String)>>(),
+            ),
+            _ => None,
+        };
+        let mut soups = BTreeSet::new();
+        for line in content.lines() {
+            let (path, version, checksum) = match line.split_whitespace().collect::<Vec<&str>>()[..]
+            {
+                [] => continue,
+                [path, version, checksum] => (path, version, checksum),
+                _ => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Invalid go.sum line: {}", line),
+                    })
+                }
+            };
+            // Hashes of go.mod files only, for modules that are not part of the build
+            if version.ends_with("/go.mod") {
+                continue;
+            }
+            if let Some(direct_modules) = &direct_modules {
+                if !direct_modules.contains(&(path.to_owned(), version.to_owned())) {
+                    continue;
+                }
+            }
+            let mut meta = default_meta.clone();
+            meta.insert("checksum".to_owned(), Value::from(checksum));
+            soups.insert(Soup {
+                name: path.to_owned(),
+                version: version.to_owned(),
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_SUM: &str = r#"github.com/eclipse/paho.mqtt.golang v1.4.2 h1:66wOzfUHSSI1zamx7jR6yMEI5EuHnT1G6rNA5PM12m4=
+github.com/eclipse/paho.mqtt.golang v1.4.2/go.mod h1:JGt0RsEwEX+Xa/agj90YJ9d9DH2b7upDZMK9HRbFvCA=
+github.com/gorilla/websocket v1.4.2 h1:+/TMaTYc4QFitKJxsQ7Yye35DkWvkdLcvGKqM+x0Ufc=
+github.com/gorilla/websocket v1.4.2/go.mod h1:YR8l580nyteQvAITg2hZ9XVh4b55+EU/adAjf1fMHhE=
+golang.org/x/sync v0.0.0-20210220032951-036812b2e83c/go.mod h1:RxMgew5VJxzue5WJ+9gRIrK6b8fu8XEyZGO6cgb4/G8=
+"#;
+
+    #[test]
+    fn direct_modules() {
+        let go_mod = "require (\n\tgithub.com/eclipse/paho.mqtt.golang v1.4.2\n\tgithub.com/gorilla/websocket v1.4.2 // indirect\n)";
+        let result = GoSum {
+            include_transitive: false,
+            go_mod: Some(go_mod.to_owned()),
+        }
+        .soups(GO_SUM, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("github.com/eclipse/paho.mqtt.golang", soup.name);
+        assert_eq!("v1.4.2", soup.version);
+        assert_eq!(
+            Some(&Value::from(
+                "h1:66wOzfUHSSI1zamx7jR6yMEI5EuHnT1G6rNA5PM12m4="
+            )),
+            soup.meta.get("checksum")
+        );
+    }
+
+    #[test]
+    fn transitive_modules() {
+        let result = GoSum {
+            include_transitive: true,
+            go_mod: None,
+        }
+        .soups(GO_SUM, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(2, result.unwrap().len());
+    }
+
+    #[test]
+    fn invalid_line() {
+        let result = GoSum {
+            include_transitive: true,
+            go_mod: None,
+        }
+        .soups("github.com/gorilla/websocket v1.4.2", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
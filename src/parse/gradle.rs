@@ -0,0 +1,179 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct Gradle {}
+
+lazy_static! {
+    static ref BLOCK_COMMENT: Regex = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    static ref LINE_COMMENT: Regex = Regex::new(r"(?m)^\s*//.*$").unwrap();
+    static ref STRING_NOTATION: Regex = Regex::new(
+        r#"(?m)^\s*(?P<configuration>[A-Za-z]+)\s*\(?\s*["'](?P<group>[^:"'\s]+):(?P<artifact>[^:"'\s@]+)(?::(?P<version>[^:"'\s@]+))?(?::[^"'\s@]+)?(?:@[^"'\s]+)?["']"#
+    )
+    .unwrap();
+    static ref MAP_NOTATION: Regex = Regex::new(
+        r#"(?m)^\s*(?P<configuration>[A-Za-z]+)\s*\(?\s*group\s*[:=]\s*["'](?P<group>[^"']+)["']\s*,\s*name\s*[:=]\s*["'](?P<artifact>[^"']+)["'](?:\s*,\s*version\s*[:=]\s*["'](?P<version>[^"']+)["'])?"#
+    )
+    .unwrap();
+}
+
+const CONFIGURATIONS: [&str; 10] = [
+    "api",
+    "implementation",
+    "compileOnly",
+    "compileOnlyApi",
+    "runtimeOnly",
+    "compile",
+    "runtime",
+    "annotationProcessor",
+    "kapt",
+    "ksp",
+];
+
+impl SoupParse for Gradle {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content = BLOCK_COMMENT.replace_all(content, "");
+        let content = LINE_COMMENT.replace_all(&content, "");
+        let soups = STRING_NOTATION
+            .captures_iter(&content)
+            .chain(MAP_NOTATION.captures_iter(&content))
+            .filter(|captures| is_included_configuration(&captures["configuration"]))
+            .map(|captures| soup(&captures, default_meta))
+            .collect();
+        Ok(soups)
+    }
+}
+
+/// Matches the standard configurations, also when prefixed by a source set or variant such as
+/// `debugImplementation`. Test configurations are left out.
+fn is_included_configuration(configuration: &str) -> bool {
+    if configuration.starts_with("test") || configuration.starts_with("androidTest") {
+        return false;
+    }
+    CONFIGURATIONS.iter().any(|base| {
+        let mut capitalized = base[..1].to_uppercase();
+        capitalized.push_str(&base[1..]);
+        configuration == *base || configuration.ends_with(&capitalized)
+    })
+}
+
+fn soup(captures: &Captures, default_meta: &Map<String, Value>) -> Soup {
+    let group = &captures["group"];
+    let artifact = &captures["artifact"];
+    let version = match captures.name("version") {
+        Some(version) => version.as_str(),
+        None => "unknown",
+    };
+    let mut meta = default_meta.clone();
+    meta.insert("group".to_owned(), Value::from(group));
+    meta.insert("artifact".to_owned(), Value::from(artifact));
+    meta.insert(
+        "configuration".to_owned(),
+        Value::from(&captures["configuration"]),
+    );
+    Soup {
+        name: format!("{}:{}", group, artifact),
+        version: version.to_owned(),
+        meta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test]
+    fn groovy_dependencies() {
+        let content = r#"
+plugins {
+    id 'java'
+}
+
+dependencies {
+    implementation 'com.google.guava:guava:31.1-jre'
+    api "org.slf4j:slf4j-api:2.0.3"
+    runtimeOnly group: 'org.postgresql', name: 'postgresql', version: '42.5.0'
+    implementation platform('org.springframework.boot:spring-boot-dependencies:2.7.5')
+    implementation 'org.springframework.boot:spring-boot-starter-web'
+    // implementation 'commons-io:commons-io:2.11.0'
+    testImplementation 'junit:junit:4.13.2'
+}
+        "#;
+        let result = Gradle {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("com.google.guava:guava", "31.1-jre"),
+                soup("org.postgresql:postgresql", "42.5.0"),
+                soup("org.slf4j:slf4j-api", "2.0.3"),
+                soup(
+                    "org.springframework.boot:spring-boot-starter-web",
+                    "unknown"
+                ),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn kotlin_dependencies() {
+        let content = r#"
+dependencies {
+    implementation("com.squareup.okhttp3:okhttp:4.10.0")
+    debugImplementation("com.squareup.leakcanary:leakcanary-android:2.9.1")
+    kapt("com.google.dagger:dagger-compiler:2.44")
+    /*
+    implementation("commons-io:commons-io:2.11.0")
+    */
+    androidTestImplementation("androidx.test.espresso:espresso-core:3.5.0")
+}
+        "#;
+        let result = Gradle {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("com.google.dagger:dagger-compiler", "2.44"),
+                soup("com.squareup.leakcanary:leakcanary-android", "2.9.1"),
+                soup("com.squareup.okhttp3:okhttp", "4.10.0"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn group_and_artifact_meta() {
+        let content = r#"implementation("com.squareup.okhttp3:okhttp:4.10.0")"#;
+        let soups = Gradle {}.soups(content, &Map::new()).unwrap();
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!(
+            Some(&Value::from("com.squareup.okhttp3")),
+            soup.meta.get("group")
+        );
+        assert_eq!(Some(&Value::from("okhttp")), soup.meta.get("artifact"));
+        assert_eq!(
+            Some(&Value::from("implementation")),
+            soup.meta.get("configuration")
+        );
+    }
+
+    #[test_case("implementation", true)]
+    #[test_case("releaseApi", true)]
+    #[test_case("testImplementation", false)]
+    #[test_case("classpath", false)]
+    fn configurations(configuration: &str, expected: bool) {
+        assert_eq!(expected, is_included_configuration(configuration));
+    }
+}
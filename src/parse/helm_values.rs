@@ -0,0 +1,172 @@
+use super::{docker_base, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Container images configured in Helm chart values, given either as a reference string or
+/// as a mapping with `registry`, `repository`, `tag` and `digest`.
+pub struct HelmValues {}
+
+impl SoupParse for HelmValues {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: serde_yaml::Value = match serde_yaml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Helm values structure ({})", e),
+                })
+            }
+        };
+        let mut images = Vec::new();
+        find_images(&content, &mut images);
+        Ok(images
+            .into_iter()
+            .map(|image| docker_base::image_soup(&image, default_meta))
+            .collect())
+    }
+}
+
+fn find_images(value: &serde_yaml::Value, images: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let image = match key.as_str() {
+                    Some("image") => image_reference(value),
+                    _ => None,
+                };
+                match image {
+                    Some(image) => images.push(image),
+                    None => find_images(value, images),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            for value in sequence {
+                find_images(value, images);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn image_reference(value: &serde_yaml::Value) -> Option<String> {
+    if let Some(image) = value.as_str() {
+        return match image.is_empty() {
+            true => None,
+            false => Some(image.to_owned()),
+        };
+    }
+    let repository = value
+        .get("repository")
+        .and_then(serde_yaml::Value::as_str)?;
+    let mut image = match value.get("registry").and_then(serde_yaml::Value::as_str) {
+        Some(registry) if !registry.is_empty() => format!("{}/{}", registry, repository),
+        _ => repository.to_owned(),
+    };
+    match value.get("tag") {
+        Some(serde_yaml::Value::String(tag)) if !tag.is_empty() => {
+            image.push(':');
+            image.push_str(tag);
+        }
+        Some(serde_yaml::Value::Number(tag)) if tag.is_u64() || tag.is_i64() => {
+            image.push(':');
+            image.push_str(&tag.to_string());
+        }
+        // The written tag is lost once parsed as a float, e.g. `7.10` becomes `7.1`
+        Some(serde_yaml::Value::Number(tag)) => {
+            eprintln!(
+                "Skipping image {} with unquoted tag {}, it must be quoted",
+                repository, tag
+            );
+            return None;
+        }
+        _ => {}
+    }
+    if let Some(digest) = value.get("digest").and_then(serde_yaml::Value::as_str) {
+        if !digest.is_empty() {
+            image.push('@');
+            image.push_str(digest);
+        }
+    }
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_images() {
+        let content = r#"
+replicaCount: 2
+image:
+  repository: registry.example.com/gateway
+  tag: "1.4.0"
+  pullPolicy: IfNotPresent
+postgresql:
+  image:
+    registry: docker.io
+    repository: bitnami/postgresql
+    tag: "14.4"
+    digest: ""
+proxy:
+  image: envoyproxy/envoy:v1.24.0
+metrics:
+  image:
+    repository: prom/statsd-exporter
+"#;
+        let result = HelmValues {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            vec![
+                ("docker.io/bitnami/postgresql".to_owned(), "14.4".to_owned()),
+                ("envoyproxy/envoy".to_owned(), "v1.24.0".to_owned()),
+                (
+                    "prom/statsd-exporter".to_owned(),
+                    docker_base::UNPINNED_VERSION.to_owned()
+                ),
+                (
+                    "registry.example.com/gateway".to_owned(),
+                    "1.4.0".to_owned()
+                ),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn numeric_tags() {
+        let content = r#"
+image:
+  repository: postgres
+  tag: 14
+sidecar:
+  image:
+    repository: elasticsearch
+    tag: 7.10
+"#;
+        let result = HelmValues {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let versions = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(vec![("postgres".to_owned(), "14".to_owned())], versions);
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        let result = HelmValues {}.soups("image: [", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use super::{docker_base, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Container images of workloads in Kubernetes manifests, which may hold several documents.
+pub struct Kubernetes {}
+
+const CONTAINER_KEYS: [&str; 3] = ["containers", "initContainers", "ephemeralContainers"];
+
+impl SoupParse for Kubernetes {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut soups = BTreeSet::new();
+        for document in serde_yaml::Deserializer::from_str(content) {
+            let document = match serde_yaml::Value::deserialize(document) {
+                Ok(document) => document,
+                Err(e) => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Invalid Kubernetes manifest ({})", e),
+                    })
+                }
+            };
+            let mut images = Vec::new();
+            container_images(&document, &mut images);
+            soups.extend(
+                images
+                    .into_iter()
+                    .map(|image| docker_base::image_soup(image, default_meta)),
+            );
+        }
+        Ok(soups)
+    }
+}
+
+/// Returns true for manifests of Kubernetes objects, leaving out templates such as those of
+/// Helm charts, which are not valid YAML before rendering.
+pub fn is_manifest(content: &str) -> bool {
+    content.contains("apiVersion:") && content.contains("kind:") && !content.contains("{{")
+}
+
+fn container_images<'a>(value: &'a serde_yaml::Value, images: &mut Vec<&'a str>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let is_containers =
+                    matches!(key.as_str(), Some(key) if CONTAINER_KEYS.contains(&key));
+                match (is_containers, value.as_sequence()) {
+                    (true, Some(containers)) => images.extend(
+                        containers
+                            .iter()
+                            .filter_map(|container| container.get("image")?.as_str()),
+                    ),
+                    _ => container_images(value, images),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            for value in sequence {
+                container_images(value, images);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const MANIFEST: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: gateway
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          image: flyway/flyway:9.8
+      containers:
+        - name: gateway
+          image: registry.example.com/gateway:1.4.0
+        - name: proxy
+          image: envoyproxy/envoy:v1.24.0
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: backup
+spec:
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+            - name: backup
+              image: postgres:14.4
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: gateway
+"#;
+
+    #[test]
+    fn workload_images() {
+        let result = Kubernetes {}.soups(MANIFEST, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "envoyproxy/envoy",
+                "flyway/flyway",
+                "postgres",
+                "registry.example.com/gateway"
+            ],
+            names
+        );
+    }
+
+    #[test_case(MANIFEST, true)]
+    #[test_case(
+        "apiVersion: v1\nkind: Pod\nmetadata:\n  name: {{ .Release.Name }}",
+        false
+    )]
+    #[test_case("services:\n  db:\n    image: postgres", false)]
+    fn manifest_detection(content: &str, expected: bool) {
+        assert_eq!(expected, is_manifest(content));
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        let result = Kubernetes {}.soups("apiVersion: v1\nkind: [", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
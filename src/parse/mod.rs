@@ -10,8 +10,43 @@ pub trait SoupParse {
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError>;
 }
 
+/// A SOUP without meta, for comparing parse results in tests.
+#[cfg(test)]
+pub fn soup(name: &str, version: &str) -> Soup {
+    Soup {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        meta: Map::new(),
+    }
+}
+
+pub mod apk;
 pub mod apt;
 pub mod cargo;
+pub mod cargo_lock;
 pub mod csproj;
 pub mod docker_base;
+pub mod docker_compose;
+pub mod dockerfile;
+pub mod gem;
+pub mod go_mod;
+pub mod go_sum;
+pub mod gradle;
+pub mod helm_values;
+pub mod kubernetes;
+pub mod npm_global;
 pub mod package_json;
+pub mod package_lock;
+pub mod packages_config;
+pub mod packages_lock;
+pub mod pip;
+pub mod pipfile_lock;
+pub mod pnpm_lock;
+pub mod poetry_lock;
+pub mod pom;
+pub mod pyproject;
+pub mod requirements;
+pub mod rpm;
+pub mod shell;
+pub mod version_catalog;
+pub mod yarn_lock;
@@ -0,0 +1,112 @@
+use super::{shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// npm packages installed globally in Dockerfiles. Local installs are covered by package.json.
+pub struct NpmGlobal {}
+
+const OPTIONS_WITH_VALUE: [&str; 4] = ["--registry", "--prefix", "--cache", "--userconfig"];
+
+impl SoupParse for NpmGlobal {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        let content = shell::normalize(content);
+        for line in content.lines() {
+            for command in shell::commands(line) {
+                let is_global = command
+                    .iter()
+                    .any(|word| matches!(word.as_str(), "-g" | "--global" | "--location=global"));
+                if !is_global {
+                    continue;
+                }
+                let packages = match shell::subcommand_arguments(
+                    &command,
+                    &["npm"],
+                    &["install", "i", "add"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(packages) => packages,
+                    None => continue,
+                };
+                for package in packages {
+                    if let Some(soup) = package_soup(package, default_meta) {
+                        result.insert(soup);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Packages are given as `name`, `@scope/name` or either followed by `@version`. Tarballs,
+/// directories and git URLs are left out.
+fn package_soup(package: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    if package.starts_with(['$', '.', '/']) || package.contains(':') || package.ends_with(".tgz") {
+        return None;
+    }
+    let (name, version) = match package.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => (&package[..index + 1], &package[index + 2..]),
+        None => (package, "unknown"),
+    };
+    Some(Soup {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        meta: default_meta.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test]
+    fn global_packages() {
+        let input = r#"RUN npm install -g \
+        pm2@5.2.2 \
+        @angular/cli@14.2.10 \
+        typescript \
+    && npm ci"#;
+        let result = NpmGlobal {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("@angular/cli", "14.2.10"),
+                soup("pm2", "5.2.2"),
+                soup("typescript", "unknown"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("npm i --global pm2@5.2.2")]
+    #[test_case("npm install --location=global --registry https://registry.example.com pm2@5.2.2")]
+    #[test_case("npm install -g pm2@5.2.2 ./local-package git+https://github.com/some/lib.git")]
+    fn options_and_sources(input: &str) {
+        let result = NpmGlobal {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("pm2", "5.2.2")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("npm install pm2@5.2.2")]
+    #[test_case("npm uninstall -g pm2")]
+    fn no_global_packages(input: &str) {
+        let result = NpmGlobal {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+}
@@ -4,11 +4,57 @@ use serde::Deserialize;
 use serde_json::{Map, Value};
 use std::collections::{BTreeSet, HashMap};
 
-pub struct PackageJson {}
+pub struct PackageJson {
+    pub kinds: Vec<DependencyKind>,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DependencyKind {
+    Normal,
+    Optional,
+    Peer,
+    Dev,
+}
+
+pub const ALL_KINDS: [DependencyKind; 4] = [
+    DependencyKind::Normal,
+    DependencyKind::Optional,
+    DependencyKind::Peer,
+    DependencyKind::Dev,
+];
+
+impl DependencyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Optional => "optional",
+            DependencyKind::Peer => "peer",
+            DependencyKind::Dev => "dev",
+        }
+    }
+}
+
+impl Default for PackageJson {
+    fn default() -> Self {
+        PackageJson {
+            kinds: vec![
+                DependencyKind::Normal,
+                DependencyKind::Optional,
+                DependencyKind::Peer,
+            ],
+        }
+    }
+}
 
 #[derive(Deserialize)]
 struct Content {
     dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "optionalDependencies")]
+    optional_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "peerDependencies")]
+    peer_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "devDependencies")]
+    dev_dependencies: Option<HashMap<String, String>>,
 }
 
 impl SoupParse for PackageJson {
@@ -17,30 +63,92 @@ impl SoupParse for PackageJson {
         content: &str,
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
-        let parse_result: Content = match serde_json::from_str(content) {
-            Ok(content) => content,
-            Err(e) => {
-                return Err(SoupSourceParseError {
-                    message: format!("Invalid package.json structure ({})", e),
-                })
-            }
-        };
-
-        let soups = match parse_result.dependencies {
-            None => BTreeSet::new(),
-            Some(dependencies) => dependencies
-                .into_iter()
-                .map(|(key, value)| Soup {
+        let soups = dependencies(content, &self.kinds)?
+            .into_iter()
+            .map(|(key, (value, kind))| {
+                let mut meta = default_meta.clone();
+                meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+                Soup {
                     name: key,
                     version: value,
-                    meta: default_meta.clone(),
-                })
-                .collect::<BTreeSet<Soup>>(),
-        };
+                    meta,
+                }
+            })
+            .collect::<BTreeSet<Soup>>();
         Ok(soups)
     }
 }
 
+/// Returns the version range and kind of each dependency of the given kinds. A dependency
+/// listed in several sections gets the first kind in the order of `ALL_KINDS`.
+pub fn dependencies(
+    content: &str,
+    kinds: &[DependencyKind],
+) -> Result<HashMap<String, (String, DependencyKind)>, SoupSourceParseError> {
+    match serde_json::from_str(content) {
+        Ok(content) => Ok(select_dependencies(content, kinds)),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid package.json structure ({})", e),
+        }),
+    }
+}
+
+/// Same as `dependencies`, for an object with the same sections as package.json, such as
+/// the root package in a lock file.
+pub fn dependencies_from_value(
+    value: Value,
+    kinds: &[DependencyKind],
+) -> Result<HashMap<String, (String, DependencyKind)>, SoupSourceParseError> {
+    match serde_json::from_value(value) {
+        Ok(content) => Ok(select_dependencies(content, kinds)),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid package structure ({})", e),
+        }),
+    }
+}
+
+/// Returns the workspace patterns of a root package.json, given either as a list or in the
+/// `packages` of an object. Invalid content has no workspaces.
+pub fn workspaces(content: &str) -> Vec<String> {
+    let content: Value = match serde_json::from_str(content) {
+        Ok(content) => content,
+        Err(_e) => return vec![],
+    };
+    let workspaces = match content.get("workspaces") {
+        Some(Value::Object(workspaces)) => workspaces.get("packages"),
+        workspaces => workspaces,
+    };
+    workspaces
+        .and_then(Value::as_array)
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn select_dependencies(
+    mut content: Content,
+    kinds: &[DependencyKind],
+) -> HashMap<String, (String, DependencyKind)> {
+    let mut dependencies = HashMap::new();
+    for kind in ALL_KINDS.into_iter().filter(|kind| kinds.contains(kind)) {
+        let section = match kind {
+            DependencyKind::Normal => content.dependencies.take(),
+            DependencyKind::Optional => content.optional_dependencies.take(),
+            DependencyKind::Peer => content.peer_dependencies.take(),
+            DependencyKind::Dev => content.dev_dependencies.take(),
+        };
+        for (name, range) in section.unwrap_or_default() {
+            dependencies.entry(name).or_insert((range, kind));
+        }
+    }
+    dependencies
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,7 +161,7 @@ mod tests {
                 "some-lib": "^1.0.0"
             }
         }"#;
-        let result = PackageJson {}.soups(content, &Map::new());
+        let result = PackageJson::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -73,7 +181,7 @@ mod tests {
                 "another-lib": "6.6.6"
             }
         }"#;
-        let result = PackageJson {}.soups(content, &Map::new());
+        let result = PackageJson::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -101,7 +209,7 @@ mod tests {
     )]
     #[test_case("{}")]
     fn no_dependencies(input: &str) {
-        let result = PackageJson {}.soups(input, &Map::new());
+        let result = PackageJson::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
@@ -110,7 +218,75 @@ mod tests {
     #[test_case(r#"{"#)]
     #[test_case("")]
     fn fail_on_bad_json(input: &str) {
-        let result = PackageJson {}.soups(input, &Map::new());
+        let result = PackageJson::default().soups(input, &Map::new());
         assert_eq!(true, result.is_err());
     }
+
+    const ALL_SECTIONS: &str = r#"{
+        "dependencies": {
+            "some-lib": "^1.0.0"
+        },
+        "optionalDependencies": {
+            "some-optional-lib": "^2.0.0"
+        },
+        "peerDependencies": {
+            "some-peer-lib": "^3.0.0",
+            "some-dev-lib": "^4.0.0"
+        },
+        "devDependencies": {
+            "some-dev-lib": "^4.0.0"
+        }
+    }"#;
+
+    #[test_case(vec![DependencyKind::Normal], vec!["some-lib"])]
+    #[test_case(vec![DependencyKind::Optional], vec!["some-optional-lib"])]
+    #[test_case(vec![DependencyKind::Peer], vec!["some-dev-lib", "some-peer-lib"])]
+    #[test_case(vec![DependencyKind::Dev], vec!["some-dev-lib"])]
+    #[test_case(
+        ALL_KINDS.to_vec(),
+        vec!["some-dev-lib", "some-lib", "some-optional-lib", "some-peer-lib"]
+    )]
+    fn dependency_kinds(kinds: Vec<DependencyKind>, expected_names: Vec<&str>) {
+        let result = PackageJson { kinds }.soups(ALL_SECTIONS, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(expected_names, names);
+    }
+
+    #[test]
+    fn dependency_kind_meta() {
+        let result = PackageJson {
+            kinds: ALL_KINDS.to_vec(),
+        }
+        .soups(ALL_SECTIONS, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        let kind = |name: &str| {
+            soups
+                .iter()
+                .find(|soup| soup.name == name)
+                .and_then(|soup| soup.meta.get("dependency-kind"))
+                .cloned()
+        };
+        assert_eq!(Some(Value::from("normal")), kind("some-lib"));
+        assert_eq!(Some(Value::from("optional")), kind("some-optional-lib"));
+        assert_eq!(Some(Value::from("peer")), kind("some-peer-lib"));
+        assert_eq!(Some(Value::from("peer")), kind("some-dev-lib"));
+    }
+
+    #[test_case(r#"{ "workspaces": ["packages/*", "tools/cli"] }"#)]
+    #[test_case(r#"{ "workspaces": { "packages": ["packages/*", "tools/cli"] } }"#)]
+    fn workspaces(input: &str) {
+        assert_eq!(vec!["packages/*", "tools/cli"], super::workspaces(input));
+    }
+
+    #[test_case(r#"{ "name": "some-app" }"#)]
+    #[test_case(r#"{"#)]
+    fn no_workspaces(input: &str) {
+        assert_eq!(0, super::workspaces(input).len());
+    }
 }
@@ -0,0 +1,412 @@
+use super::{
+    package_json::{self, DependencyKind},
+    SoupParse,
+};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct PackageLock {
+    pub include_transitive: bool,
+    pub kinds: Vec<DependencyKind>,
+    pub package_json: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    packages: Option<HashMap<String, Value>>,
+    dependencies: Option<HashMap<String, Package>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    version: Option<String>,
+    resolved: Option<String>,
+    integrity: Option<String>,
+    link: Option<bool>,
+    dev: Option<bool>,
+    optional: Option<bool>,
+    #[serde(rename = "devOptional")]
+    dev_optional: Option<bool>,
+    peer: Option<bool>,
+    dependencies: Option<HashMap<String, Value>>,
+}
+
+impl Package {
+    /// The kinds a package is installed as, according to the flags of the lock file.
+    fn kinds(&self) -> Vec<DependencyKind> {
+        let is_set = |flag: Option<bool>| flag == Some(true);
+        match (
+            is_set(self.dev),
+            is_set(self.optional),
+            is_set(self.dev_optional),
+            is_set(self.peer),
+        ) {
+            (_, _, true, _) => vec![DependencyKind::Dev, DependencyKind::Optional],
+            (true, _, _, _) => vec![DependencyKind::Dev],
+            (_, true, _, _) => vec![DependencyKind::Optional],
+            (_, _, _, true) => vec![DependencyKind::Peer],
+            _ => vec![DependencyKind::Normal],
+        }
+    }
+}
+
+impl SoupParse for PackageLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_json::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid npm lock file structure ({})", e),
+                })
+            }
+        };
+        let (root, packages) = match (content.packages, content.dependencies) {
+            (Some(mut packages), _) => (packages.remove(""), installed_packages(packages)?),
+            (None, Some(dependencies)) => {
+                let mut packages = Vec::new();
+                flatten_dependencies(dependencies, true, &mut packages)?;
+                (None, packages)
+            }
+            (None, None) => (None, Vec::new()),
+        };
+        let direct_dependencies = match (&self.package_json, root) {
+            (Some(package_json), _) => Some(package_json::dependencies(package_json, &self.kinds)?),
+            (None, Some(root)) => Some(package_json::dependencies_from_value(root, &self.kinds)?),
+            (None, None) => None,
+        };
+
+        let mut soups = BTreeSet::new();
+        for (name, hoisted, package) in packages {
+            let kind = match (&direct_dependencies, hoisted) {
+                (Some(direct_dependencies), true) => {
+                    direct_dependencies.get(&name).map(|(_, kind)| *kind)
+                }
+                _ => None,
+            };
+            let is_direct = hoisted && (kind.is_some() || direct_dependencies.is_none());
+            if !is_direct && !self.include_transitive {
+                continue;
+            }
+            if kind.is_none() && !package.kinds().iter().any(|kind| self.kinds.contains(kind)) {
+                continue;
+            }
+            let version = match package.version {
+                Some(version) => version,
+                None => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Missing version for: {}", name),
+                    })
+                }
+            };
+            let mut meta = default_meta.clone();
+            if let Some(kind) = kind {
+                meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+            }
+            if let Some(integrity) = package.integrity {
+                meta.insert("integrity".to_owned(), Value::from(integrity));
+            }
+            if let Some(resolved) = package.resolved {
+                meta.insert("resolved".to_owned(), Value::from(resolved));
+            }
+            soups.insert(Soup {
+                name,
+                version,
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// Lock files of version 2 and 3 list every installed package by its path in `node_modules`.
+/// Packages installed directly in the top level `node_modules` are marked as hoisted.
+fn installed_packages(
+    packages: HashMap<String, Value>,
+) -> Result<Vec<(String, bool, Package)>, SoupSourceParseError> {
+    let mut installed = Vec::new();
+    for (path, value) in packages {
+        let (parent, name) = match path.rsplit_once("node_modules/") {
+            Some(parent_name) => parent_name,
+            None => continue,
+        };
+        let package: Package = match serde_json::from_value(value) {
+            Ok(package) => package,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid package: {} ({})", path, e),
+                })
+            }
+        };
+        if package.link == Some(true) {
+            continue;
+        }
+        installed.push((name.to_owned(), parent.is_empty(), package));
+    }
+    Ok(installed)
+}
+
+/// Lock files of version 1 nest dependencies that could not be hoisted to the top level.
+fn flatten_dependencies(
+    dependencies: HashMap<String, Package>,
+    hoisted: bool,
+    packages: &mut Vec<(String, bool, Package)>,
+) -> Result<(), SoupSourceParseError> {
+    for (name, mut package) in dependencies {
+        if let Some(nested) = package.dependencies.take() {
+            let nested = nested
+                .into_iter()
+                .map(|(nested_name, value)| match serde_json::from_value(value) {
+                    Ok(nested_package) => Ok((nested_name, nested_package)),
+                    Err(e) => Err(SoupSourceParseError {
+                        message: format!("Invalid dependency: {} ({})", nested_name, e),
+                    }),
+                })
+                .collect::<Result<HashMap<String, Package>, SoupSourceParseError>>()?;
+            flatten_dependencies(nested, false, packages)?;
+        }
+        packages.push((name, hoisted, package));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    const LOCK_FILE_V1: &str = r#"{
+        "name": "some-app",
+        "version": "1.0.0",
+        "lockfileVersion": 1,
+        "requires": true,
+        "dependencies": {
+            "some-lib": {
+                "version": "1.2.3",
+                "resolved": "https://registry.npmjs.org/some-lib/-/some-lib-1.2.3.tgz",
+                "integrity": "sha512-abc",
+                "requires": {
+                    "another-lib": "^2.0.0"
+                },
+                "dependencies": {
+                    "another-lib": {
+                        "version": "2.0.1",
+                        "resolved": "https://registry.npmjs.org/another-lib/-/another-lib-2.0.1.tgz",
+                        "integrity": "sha512-def"
+                    }
+                }
+            },
+            "another-lib": {
+                "version": "1.0.0",
+                "resolved": "https://registry.npmjs.org/another-lib/-/another-lib-1.0.0.tgz",
+                "integrity": "sha512-ghi"
+            }
+        }
+    }"#;
+
+    const LOCK_FILE_V3: &str = r#"{
+        "name": "some-app",
+        "version": "1.0.0",
+        "lockfileVersion": 3,
+        "requires": true,
+        "packages": {
+            "": {
+                "name": "some-app",
+                "version": "1.0.0",
+                "dependencies": {
+                    "some-lib": "^1.2.0"
+                },
+                "devDependencies": {
+                    "some-dev-lib": "^3.0.0"
+                }
+            },
+            "node_modules/some-lib": {
+                "version": "1.2.3",
+                "resolved": "https://registry.npmjs.org/some-lib/-/some-lib-1.2.3.tgz",
+                "integrity": "sha512-abc",
+                "dependencies": {
+                    "another-lib": "^2.0.0"
+                }
+            },
+            "node_modules/some-lib/node_modules/another-lib": {
+                "version": "2.0.1",
+                "resolved": "https://registry.npmjs.org/another-lib/-/another-lib-2.0.1.tgz",
+                "integrity": "sha512-def"
+            },
+            "node_modules/another-lib": {
+                "version": "1.0.0",
+                "resolved": "https://registry.npmjs.org/another-lib/-/another-lib-1.0.0.tgz",
+                "integrity": "sha512-ghi"
+            },
+            "node_modules/some-dev-lib": {
+                "version": "3.1.0",
+                "dev": true
+            },
+            "node_modules/some-workspace": {
+                "resolved": "packages/some-workspace",
+                "link": true
+            },
+            "packages/some-workspace": {
+                "version": "0.1.0"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn direct_dependencies_v1() {
+        let package_json = r#"{ "dependencies": { "some-lib": "^1.2.0" } }"#;
+        let result = PackageLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_json: Some(package_json.to_owned()),
+        }
+        .soups(LOCK_FILE_V1, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![soup("some-lib", "1.2.3")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!(Some(&Value::from("sha512-abc")), soup.meta.get("integrity"));
+        assert_eq!(
+            Some(&Value::from(
+                "https://registry.npmjs.org/some-lib/-/some-lib-1.2.3.tgz"
+            )),
+            soup.meta.get("resolved")
+        );
+    }
+
+    #[test]
+    fn direct_dependencies_v3() {
+        let result = PackageLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_json: None,
+        }
+        .soups(LOCK_FILE_V3, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("some-lib", "1.2.3")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn dependency_kinds_v3() {
+        let result = PackageLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal, DependencyKind::Dev],
+            package_json: None,
+        }
+        .soups(LOCK_FILE_V3, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![soup("some-dev-lib", "3.1.0"), soup("some-lib", "1.2.3")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let dev_lib = soups
+            .iter()
+            .find(|soup| soup.name == "some-dev-lib")
+            .unwrap();
+        assert_eq!(
+            Some(&Value::from("dev")),
+            dev_lib.meta.get("dependency-kind")
+        );
+    }
+
+    #[test_case(LOCK_FILE_V1)]
+    #[test_case(LOCK_FILE_V3)]
+    fn transitive_dependencies(input: &str) {
+        let result = PackageLock {
+            include_transitive: true,
+            kinds: vec![DependencyKind::Normal],
+            package_json: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(true, soups.contains(&soup("some-lib", "1.2.3")));
+        assert_eq!(true, soups.contains(&soup("another-lib", "1.0.0")));
+        assert_eq!(true, soups.contains(&soup("another-lib", "2.0.1")));
+        assert_eq!(
+            false,
+            soups.iter().any(|soup| soup.name == "some-workspace")
+        );
+    }
+
+    #[test_case(vec![DependencyKind::Normal], vec!["another-lib", "some-lib"])]
+    #[test_case(
+        vec![DependencyKind::Normal, DependencyKind::Dev],
+        vec!["another-lib", "dev-only-lib", "some-dev-lib", "some-lib"]
+    )]
+    #[test_case(vec![DependencyKind::Optional], vec!["dev-only-lib", "optional-lib"])]
+    fn transitive_dependency_kinds(kinds: Vec<DependencyKind>, expected_names: Vec<&str>) {
+        let input = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": {
+                    "dependencies": { "some-lib": "^1.2.0" },
+                    "devDependencies": { "some-dev-lib": "^3.0.0" }
+                },
+                "node_modules/some-lib": { "version": "1.2.3" },
+                "node_modules/another-lib": { "version": "1.0.0" },
+                "node_modules/some-dev-lib": { "version": "3.1.0", "dev": true },
+                "node_modules/dev-only-lib": { "version": "2.0.0", "devOptional": true },
+                "node_modules/optional-lib": { "version": "0.4.0", "optional": true }
+            }
+        }"#;
+        let result = PackageLock {
+            include_transitive: true,
+            kinds,
+            package_json: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let mut names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        names.sort();
+        assert_eq!(expected_names, names);
+    }
+
+    #[test_case(r#"{ "lockfileVersion": 3, "packages": { "": {} } }"#)]
+    #[test_case("{}")]
+    fn no_dependencies(input: &str) {
+        let result = PackageLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_json: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test_case(r#"{"#)]
+    #[test_case("")]
+    fn fail_on_bad_json(input: &str) {
+        let result = PackageLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_json: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct PackagesConfig {}
+
+impl SoupParse for PackagesConfig {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut reader = Reader::from_str(content);
+        reader.trim_text(true);
+        reader.expand_empty_elements(true);
+
+        let mut soups: BTreeSet<Soup> = BTreeSet::new();
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event(&mut buf) {
+                Ok(Event::Start(ref e)) => {
+                    if let b"package" = e.name() {
+                        if let Some(soup) = package_soup(e, default_meta)? {
+                            soups.insert(soup);
+                        }
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Invalid XML structure {}", e),
+                    });
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(soups)
+    }
+}
+
+/// Development dependencies, such as analyzers, are left out.
+fn package_soup(
+    element: &BytesStart,
+    default_meta: &Map<String, Value>,
+) -> Result<Option<Soup>, SoupSourceParseError> {
+    if attribute_value(element, "developmentDependency")?.as_deref() == Some("true") {
+        return Ok(None);
+    }
+    let name = match attribute_value(element, "id")? {
+        Some(name) => name,
+        None => {
+            return Err(SoupSourceParseError {
+                message: "Missing required attribute: id".to_owned(),
+            })
+        }
+    };
+    let version = match attribute_value(element, "version")? {
+        Some(version) => version,
+        None => "unknown".to_owned(),
+    };
+    let mut meta = default_meta.clone();
+    if let Some(target_framework) = attribute_value(element, "targetFramework")? {
+        meta.insert("target-framework".to_owned(), Value::from(target_framework));
+    }
+    Ok(Some(Soup {
+        name,
+        version,
+        meta,
+    }))
+}
+
+fn attribute_value(
+    element: &BytesStart,
+    key: &str,
+) -> Result<Option<String>, SoupSourceParseError> {
+    let attribute = element
+        .attributes()
+        .filter_map(|attribute| attribute.ok())
+        .find(|attribute| attribute.key == key.as_bytes());
+    match attribute {
+        Some(attribute) => match String::from_utf8(attribute.value.to_vec()) {
+            Ok(value) => Ok(Some(value)),
+            Err(_e) => Err(SoupSourceParseError {
+                message: format!("Unable to parse attribute {} as utf8", key),
+            }),
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packages() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<packages>
+  <package id="Newtonsoft.Json" version="12.0.1" targetFramework="net472" />
+  <package id="log4net" version="2.0.15" targetFramework="net472" />
+  <package id="StyleCop.Analyzers" version="1.1.118" targetFramework="net472" developmentDependency="true" />
+</packages>
+        "#;
+        let result = PackagesConfig {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                ("Newtonsoft.Json".to_owned(), "12.0.1".to_owned()),
+                ("log4net".to_owned(), "2.0.15".to_owned()),
+            ],
+            soups
+                .iter()
+                .map(|soup| (soup.name.to_owned(), soup.version.to_owned()))
+                .collect::<Vec<(String, String)>>()
+        );
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!(
+            Some(&Value::from("net472")),
+            soup.meta.get("target-framework")
+        );
+    }
+
+    #[test]
+    fn missing_id() {
+        let content = r#"<packages><package version="12.0.1" /></packages>"#;
+        let result = PackagesConfig {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
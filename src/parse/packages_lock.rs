@@ -0,0 +1,132 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct PackagesLock {
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    dependencies: Option<HashMap<String, HashMap<String, Package>>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    #[serde(rename = "type")]
+    package_type: String,
+    resolved: Option<String>,
+    #[serde(rename = "contentHash")]
+    content_hash: Option<String>,
+}
+
+impl SoupParse for PackagesLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_json::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid packages.lock.json structure ({})", e),
+                })
+            }
+        };
+        let mut soups = BTreeSet::new();
+        for packages in content.dependencies.unwrap_or_default().into_values() {
+            for (name, package) in packages {
+                let include = match package.package_type.as_str() {
+                    "Direct" => true,
+                    "Transitive" | "CentralTransitive" => self.include_transitive,
+                    _ => false,
+                };
+                if !include {
+                    continue;
+                }
+                let version = match package.resolved {
+                    Some(version) => version,
+                    None => {
+                        return Err(SoupSourceParseError {
+                            message: format!("Missing version for: {}", name),
+                        })
+                    }
+                };
+                let mut meta = default_meta.clone();
+                if let Some(content_hash) = package.content_hash {
+                    meta.insert("content-hash".to_owned(), Value::from(content_hash));
+                }
+                soups.insert(Soup {
+                    name,
+                    version,
+                    meta,
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCK_FILE: &str = r#"{
+        "version": 1,
+        "dependencies": {
+            "net6.0": {
+                "Serilog.Sinks.Console": {
+                    "type": "Direct",
+                    "requested": "[4.1.0, )",
+                    "resolved": "4.1.0",
+                    "contentHash": "K6N5q+5fetjnJPvCmkWOpJ/V8IEIoMIB1s86OzBrbxwTyHxdx3pmz4H+8+O/Dc/ftUX12DM1aynx/dDowkwzqg==",
+                    "dependencies": {
+                        "Serilog": "2.10.0"
+                    }
+                },
+                "Serilog": {
+                    "type": "Transitive",
+                    "resolved": "2.10.0",
+                    "contentHash": "+QX0hmf37a0/OZLxM3wL7V6/ADvC1XihXN4Kq/p6d8lCPfgkRdiuhbWlMaFjR9Av0dy5F0+MBeDmDdRZN/YwQA=="
+                },
+                "common": {
+                    "type": "Project"
+                }
+            },
+            "net7.0": {
+                "Serilog.Sinks.Console": {
+                    "type": "Direct",
+                    "requested": "[4.1.0, )",
+                    "resolved": "4.1.0"
+                }
+            }
+        }
+    }"#;
+
+    #[test_case(false, vec!["Serilog.Sinks.Console"])]
+    #[test_case(true, vec!["Serilog", "Serilog.Sinks.Console"])]
+    fn packages(include_transitive: bool, expected_names: Vec<&str>) {
+        let result = PackagesLock { include_transitive }.soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(expected_names, names);
+    }
+
+    #[test_case(r#"{"#)]
+    #[test_case(r#"{ "dependencies": { "net6.0": { "Serilog": { "type": "Direct" } } } }"#)]
+    fn invalid_lock_file(input: &str) {
+        let result = PackagesLock {
+            include_transitive: false,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use super::{requirements, shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Python packages installed with pip in Dockerfiles.
+pub struct Pip {}
+
+const OPTIONS_WITH_VALUE: [&str; 37] = [
+    "-r",
+    "--requirement",
+    "-c",
+    "--constraint",
+    "-e",
+    "--editable",
+    "-i",
+    "--index-url",
+    "--extra-index-url",
+    "-f",
+    "--find-links",
+    "-t",
+    "--target",
+    "--prefix",
+    "--root",
+    "--src",
+    "--trusted-host",
+    "--platform",
+    "--python-version",
+    "--implementation",
+    "--abi",
+    "--upgrade-strategy",
+    "--cache-dir",
+    "--proxy",
+    "--no-binary",
+    "--only-binary",
+    "--progress-bar",
+    "--root-user-action",
+    "--report",
+    "-C",
+    "--config-settings",
+    "--global-option",
+    "--log",
+    "--retries",
+    "--timeout",
+    "--exists-action",
+    "--cert",
+];
+
+impl SoupParse for Pip {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        let content = shell::normalize(content);
+        for line in content.lines() {
+            for command in shell::commands(line) {
+                let packages = match shell::subcommand_arguments(
+                    &command,
+                    &["pip", "pip3", "pip2"],
+                    &["install"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(packages) => packages,
+                    None => continue,
+                };
+                for package in packages {
+                    if package.starts_with(['$', '.'])
+                        || package.contains('/')
+                        || package.ends_with(".whl")
+                        || package.ends_with(".tar.gz")
+                    {
+                        continue;
+                    }
+                    if let Ok(Some(soup)) = requirements::parse_requirement(package, default_meta) {
+                        result.insert(soup);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test]
+    fn multiple_packages() {
+        let input = r#"RUN pip install --no-cache-dir \
+        requests==2.28.1 \
+        "uvicorn[standard]>=0.19" \
+        gunicorn \
+    && pip3 install -r requirements.txt"#;
+        let result = Pip {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("gunicorn", "unknown"),
+                soup("requests", "2.28.1"),
+                soup("uvicorn", ">=0.19"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("python -m pip install --upgrade requests==2.28.1")]
+    #[test_case("pip install -i https://pypi.example.com/simple requests==2.28.1 $EXTRA")]
+    #[test_case("pip install requests==2.28.1 ./local-package dist/some.whl")]
+    #[test_case("pip install --progress-bar off --no-binary :all: requests==2.28.1")]
+    #[test_case("pip install --only-binary=:all: --timeout 60 requests==2.28.1")]
+    fn options_and_paths(input: &str) {
+        let result = Pip {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("requests", "2.28.1")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("pip install -r requirements.txt")]
+    #[test_case("pip uninstall -y requests")]
+    #[test_case("RUN echo pip install requests")]
+    #[test_case("RUN printf 'pip install requests' > install.sh")]
+    fn no_packages(input: &str) {
+        let result = Pip {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+}
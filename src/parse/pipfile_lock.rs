@@ -0,0 +1,211 @@
+use super::{requirements, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+pub struct PipfileLock {
+    pub include_transitive: bool,
+    pub pipfile: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    default: Option<HashMap<String, Package>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    version: Option<String>,
+    hashes: Option<Vec<String>>,
+    markers: Option<String>,
+    git: Option<String>,
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+    path: Option<String>,
+    editable: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct Pipfile {
+    packages: Option<HashMap<String, toml::Value>>,
+}
+
+impl SoupParse for PipfileLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_json::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Pipfile.lock structure ({})", e),
+                })
+            }
+        };
+        let direct_dependencies = match (&self.pipfile, self.include_transitive) {
+            (Some(pipfile), false) => Some(pipfile_packages(pipfile)?),
+            _ => None,
+        };
+        let mut soups = BTreeSet::new();
+        for (name, package) in content.default.unwrap_or_default() {
+            if let Some(direct_dependencies) = &direct_dependencies {
+                if !direct_dependencies.contains(&requirements::normalize_name(&name)) {
+                    continue;
+                }
+            }
+            if package.path.is_some() {
+                continue;
+            }
+            let version = match (package.version, package.git, package.reference) {
+                (Some(version), _, _) => version.trim_start_matches("==").to_owned(),
+                (None, Some(_), Some(reference)) => reference,
+                (None, Some(_), None) => "unknown".to_owned(),
+                (None, None, _) if package.editable == Some(true) => continue,
+                (None, None, _) => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Missing version for: {}", name),
+                    })
+                }
+            };
+            let mut meta = default_meta.clone();
+            if let Some(markers) = package.markers {
+                meta.insert("environment-marker".to_owned(), Value::from(markers));
+            }
+            if let Some(hashes) = package.hashes {
+                meta.insert("hashes".to_owned(), Value::from(hashes));
+            }
+            soups.insert(Soup {
+                name,
+                version,
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+fn pipfile_packages(content: &str) -> Result<HashSet<String>, SoupSourceParseError> {
+    let pipfile: Pipfile = match toml::from_str(content) {
+        Ok(pipfile) => pipfile,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid Pipfile ({})", e),
+            })
+        }
+    };
+    Ok(pipfile
+        .packages
+        .unwrap_or_default()
+        .keys()
+        .map(|name| requirements::normalize_name(name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCK_FILE: &str = r#"{
+        "_meta": {
+            "hash": { "sha256": "abc" },
+            "pipfile-spec": 6,
+            "requires": { "python_version": "3.9" }
+        },
+        "default": {
+            "certifi": {
+                "hashes": ["sha256:0d9c601124e5a6ba9712dbc60d9c53c21e34f5f641fe83002317394311bdce14"],
+                "markers": "python_version >= '3.6'",
+                "version": "==2022.9.24"
+            },
+            "requests": {
+                "hashes": ["sha256:8fefa2a1a1365bf5520aac41836fbee479da67864514bdb821f31ce07ce65349"],
+                "index": "pypi",
+                "version": "==2.28.1"
+            },
+            "some-lib": {
+                "git": "https://github.com/some/lib.git",
+                "ref": "9f35b8e"
+            }
+        },
+        "develop": {
+            "pytest": {
+                "version": "==7.1.3"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn direct_dependencies() {
+        let pipfile = "[packages]\nrequests = \"*\"\nsome_lib = { git = \"https://github.com/some/lib.git\" }\n\n[dev-packages]\npytest = \"*\"";
+        let result = PipfileLock {
+            include_transitive: false,
+            pipfile: Some(pipfile.to_owned()),
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "requests".to_owned(),
+                    version: "2.28.1".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "some-lib".to_owned(),
+                    version: "9f35b8e".to_owned(),
+                    meta: Map::new()
+                }
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let requests = soups.iter().find(|soup| soup.name == "requests").unwrap();
+        assert_eq!(
+            Some(&serde_json::json!([
+                "sha256:8fefa2a1a1365bf5520aac41836fbee479da67864514bdb821f31ce07ce65349"
+            ])),
+            requests.meta.get("hashes")
+        );
+    }
+
+    #[test]
+    fn transitive_dependencies() {
+        let result = PipfileLock {
+            include_transitive: true,
+            pipfile: None,
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(3, result.unwrap().len());
+    }
+
+    #[test_case(r#"{ "default": { "some-app": { "editable": true, "path": "." } } }"#)]
+    #[test_case(r#"{ "default": { "some-lib": { "path": "./libs/some-lib" } } }"#)]
+    #[test_case(r#"{ "default": { "some-app": { "editable": true } } }"#)]
+    fn path_dependency(input: &str) {
+        let result = PipfileLock {
+            include_transitive: true,
+            pipfile: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test_case(r#"{"#)]
+    #[test_case(r#"{ "default": { "requests": {} } }"#)]
+    fn invalid_lock_file(input: &str) {
+        let result = PipfileLock {
+            include_transitive: true,
+            pipfile: None,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use super::{package_json::DependencyKind, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct PnpmLock {
+    pub include_transitive: bool,
+    pub kinds: Vec<DependencyKind>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(rename = "lockfileVersion")]
+    lockfile_version: Option<serde_yaml::Value>,
+    #[serde(flatten)]
+    root: Importer,
+    importers: Option<HashMap<String, Importer>>,
+    packages: Option<HashMap<String, Package>>,
+}
+
+#[derive(Deserialize)]
+struct Importer {
+    dependencies: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(rename = "optionalDependencies")]
+    optional_dependencies: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(rename = "devDependencies")]
+    dev_dependencies: Option<HashMap<String, serde_yaml::Value>>,
+}
+
+impl Importer {
+    fn dependencies(
+        self,
+        kinds: &[DependencyKind],
+    ) -> Vec<(String, serde_yaml::Value, DependencyKind)> {
+        [
+            (DependencyKind::Normal, self.dependencies),
+            (DependencyKind::Optional, self.optional_dependencies),
+            (DependencyKind::Dev, self.dev_dependencies),
+        ]
+        .into_iter()
+        .filter(|(kind, _)| kinds.contains(kind))
+        .flat_map(|(kind, dependencies)| {
+            dependencies
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |(name, value)| (name, value, kind))
+        })
+        .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct Package {
+    resolution: Option<Resolution>,
+    dev: Option<bool>,
+    optional: Option<bool>,
+}
+
+impl Package {
+    /// Lock files before version 9 mark packages only needed for development or optionally.
+    fn kind(&self) -> DependencyKind {
+        match (self.dev, self.optional) {
+            (Some(true), _) => DependencyKind::Dev,
+            (_, Some(true)) => DependencyKind::Optional,
+            _ => DependencyKind::Normal,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Resolution {
+    integrity: Option<String>,
+}
+
+impl SoupParse for PnpmLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_yaml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid pnpm-lock.yaml structure ({})", e),
+                })
+            }
+        };
+        let slash_separated = match &content.lockfile_version {
+            Some(serde_yaml::Value::Number(version)) => version.as_f64().unwrap_or_default() < 6.0,
+            Some(serde_yaml::Value::String(version)) => {
+                version.parse::<f64>().unwrap_or_default() < 6.0
+            }
+            _ => false,
+        };
+        let packages = content
+            .packages
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(key, package)| {
+                let (name, version) = parse_package_key(&key, slash_separated)?;
+                let kind = package.kind();
+                let integrity = package.resolution.and_then(|r| r.integrity);
+                Some(((name, version), (kind, integrity)))
+            })
+            .collect::<HashMap<(String, String), (DependencyKind, Option<String>)>>();
+
+        let mut importers = vec![content.root];
+        importers.extend(content.importers.unwrap_or_default().into_values());
+        let mut direct_dependencies = HashMap::new();
+        for (name, value, kind) in importers
+            .into_iter()
+            .flat_map(|importer| importer.dependencies(&self.kinds))
+        {
+            let version = match &value {
+                serde_yaml::Value::String(version) => Some(version.as_str()),
+                _ => value.get("version").and_then(serde_yaml::Value::as_str),
+            };
+            let version = match version {
+                Some(version) => strip_peer_suffix(version),
+                None => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Missing version for: {}", name),
+                    })
+                }
+            };
+            if version.starts_with("link:") {
+                continue;
+            }
+            direct_dependencies
+                .entry((name, version.to_owned()))
+                .or_insert(kind);
+        }
+
+        let mut locked_versions = direct_dependencies.keys().cloned().collect::<Vec<_>>();
+        if self.include_transitive {
+            locked_versions.extend(
+                packages
+                    .iter()
+                    .filter(|(_, (kind, _))| self.kinds.contains(kind))
+                    .map(|(key, _)| key.clone()),
+            );
+        }
+        Ok(locked_versions
+            .into_iter()
+            .map(|(name, version)| {
+                let mut meta = default_meta.clone();
+                let key = (name, version);
+                if let Some(kind) = direct_dependencies.get(&key) {
+                    meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+                }
+                if let Some((_, Some(integrity))) = packages.get(&key) {
+                    meta.insert("integrity".to_owned(), Value::from(integrity.as_str()));
+                }
+                let (name, version) = key;
+                Soup {
+                    name,
+                    version,
+                    meta,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Package keys are `/name/1.0.0` before lock file version 6, `/name@1.0.0` in version 6
+/// and `name@1.0.0` from version 9.
+fn parse_package_key(key: &str, slash_separated: bool) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let (name, version) = match slash_separated {
+        true => key.rsplit_once('/')?,
+        false => {
+            let separator = key.get(1..)?.find('@')? + 1;
+            (&key[..separator], &key[separator + 1..])
+        }
+    };
+    Some((name.to_owned(), strip_peer_suffix(version).to_owned()))
+}
+
+fn strip_peer_suffix(version: &str) -> &str {
+    match version.find(['(', '_']) {
+        Some(index) => &version[..index],
+        None => version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    const LOCK_FILE_V5: &str = r#"
+lockfileVersion: 5.4
+
+specifiers:
+  lodash: ^4.17.21
+  react-dom: ^18.2.0
+
+dependencies:
+  lodash: 4.17.21
+  react-dom: 18.2.0_react@18.2.0
+
+packages:
+
+  /lodash/4.17.21:
+    resolution: {integrity: sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg==}
+    dev: false
+
+  /react-dom/18.2.0_react@18.2.0:
+    resolution: {integrity: sha512-abc}
+    dev: false
+
+  /react/18.2.0:
+    resolution: {integrity: sha512-def}
+    dev: false
+"#;
+
+    const LOCK_FILE_V6: &str = r#"
+lockfileVersion: '6.0'
+
+dependencies:
+  lodash:
+    specifier: ^4.17.21
+    version: 4.17.21
+  react-dom:
+    specifier: ^18.2.0
+    version: 18.2.0(react@18.2.0)
+
+packages:
+
+  /lodash@4.17.21:
+    resolution: {integrity: sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg==}
+    dev: false
+
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-abc}
+    dev: false
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-def}
+    dev: false
+"#;
+
+    const LOCK_FILE_V9: &str = r#"
+lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      lodash:
+        specifier: ^4.17.21
+        version: 4.17.21
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+      some-workspace:
+        specifier: workspace:*
+        version: link:packages/some-workspace
+
+packages:
+
+  lodash@4.17.21:
+    resolution: {integrity: sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg==}
+
+  react-dom@18.2.0:
+    resolution: {integrity: sha512-abc}
+
+  react@18.2.0:
+    resolution: {integrity: sha512-def}
+
+snapshots:
+
+  lodash@4.17.21: {}
+"#;
+
+    #[test_case(LOCK_FILE_V5)]
+    #[test_case(LOCK_FILE_V6)]
+    #[test_case(LOCK_FILE_V9)]
+    fn direct_dependencies(input: &str) {
+        let result = PnpmLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![soup("lodash", "4.17.21"), soup("react-dom", "18.2.0")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let react_dom = soups.iter().find(|soup| soup.name == "react-dom").unwrap();
+        assert_eq!(
+            Some(&Value::from("sha512-abc")),
+            react_dom.meta.get("integrity")
+        );
+    }
+
+    #[test_case(LOCK_FILE_V5)]
+    #[test_case(LOCK_FILE_V6)]
+    #[test_case(LOCK_FILE_V9)]
+    fn transitive_dependencies(input: &str) {
+        let result = PnpmLock {
+            include_transitive: true,
+            kinds: vec![DependencyKind::Normal],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("lodash", "4.17.21"),
+                soup("react", "18.2.0"),
+                soup("react-dom", "18.2.0")
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case(vec![DependencyKind::Normal], vec!["lodash"])]
+    #[test_case(vec![DependencyKind::Normal, DependencyKind::Dev], vec!["jest", "lodash"])]
+    fn transitive_dependency_kinds(kinds: Vec<DependencyKind>, expected_names: Vec<&str>) {
+        let input = r#"
+lockfileVersion: '6.0'
+
+packages:
+
+  /lodash@4.17.21:
+    resolution: {integrity: sha512-abc}
+    dev: false
+
+  /jest@29.3.1:
+    resolution: {integrity: sha512-def}
+    dev: true
+"#;
+        let result = PnpmLock {
+            include_transitive: true,
+            kinds,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(expected_names, names);
+    }
+
+    #[test_case("/@babel/core/7.20.0", true, "@babel/core", "7.20.0")]
+    #[test_case("/string_decoder/1.3.0", true, "string_decoder", "1.3.0")]
+    #[test_case("/@babel/core@7.20.0", false, "@babel/core", "7.20.0")]
+    #[test_case(
+        "@babel/core@7.20.0(supports-color@5.5.0)",
+        false,
+        "@babel/core",
+        "7.20.0"
+    )]
+    fn package_key(key: &str, slash_separated: bool, expected_name: &str, expected_version: &str) {
+        assert_eq!(
+            Some((expected_name.to_owned(), expected_version.to_owned())),
+            parse_package_key(key, slash_separated)
+        );
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        let result = PnpmLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+        }
+        .soups("lockfileVersion: [6", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
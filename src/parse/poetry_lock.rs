@@ -0,0 +1,131 @@
+use super::{pyproject, requirements, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct PoetryLock {
+    pub include_transitive: bool,
+    pub pyproject: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    package: Option<Vec<Package>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+}
+
+impl SoupParse for PoetryLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match toml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid poetry.lock ({})", e),
+                });
+            }
+        };
+        let direct_dependencies = match (&self.pyproject, self.include_transitive) {
+            (Some(pyproject), false) => Some(pyproject::dependency_names(pyproject)?),
+            _ => None,
+        };
+        Ok(content
+            .package
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|package| match &direct_dependencies {
+                Some(direct_dependencies) => {
+                    direct_dependencies.contains(&requirements::normalize_name(&package.name))
+                }
+                None => true,
+            })
+            .map(|package| Soup {
+                name: package.name,
+                version: package.version,
+                meta: default_meta.clone(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK_FILE: &str = r#"
+[[package]]
+name = "certifi"
+version = "2022.9.24"
+description = "Python package for providing Mozilla's CA Bundle."
+category = "main"
+optional = false
+python-versions = ">=3.6"
+
+[[package]]
+name = "requests"
+version = "2.28.1"
+description = "Python HTTP for Humans."
+category = "main"
+optional = false
+python-versions = ">=3.7, <4"
+
+[package.dependencies]
+certifi = ">=2017.4.17"
+
+[metadata]
+lock-version = "1.1"
+python-versions = "^3.9"
+content-hash = "abc"
+"#;
+
+    #[test]
+    fn direct_dependencies() {
+        let pyproject = "[tool.poetry.dependencies]\npython = \"^3.9\"\nRequests = \"^2.28\"";
+        let result = PoetryLock {
+            include_transitive: false,
+            pyproject: Some(pyproject.to_owned()),
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![Soup {
+                name: "requests".to_owned(),
+                version: "2.28.1".to_owned(),
+                meta: Map::new()
+            }]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn transitive_dependencies() {
+        let result = PoetryLock {
+            include_transitive: true,
+            pyproject: None,
+        }
+        .soups(LOCK_FILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(2, result.unwrap().len());
+    }
+
+    #[test]
+    fn invalid_lock_file() {
+        let result = PoetryLock {
+            include_transitive: true,
+            pyproject: None,
+        }
+        .soups("[[package]]\nname = \"requests\"", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,508 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct Pom {
+    /// Content of the parent POMs found in the repository, starting with the closest one
+    pub parent_poms: Vec<String>,
+}
+
+lazy_static! {
+    static ref PROPERTY: Regex = Regex::new(r"\$\{(?P<name>[^}]+)\}").unwrap();
+}
+
+const MAX_INTERPOLATION_DEPTH: usize = 10;
+
+#[derive(Default)]
+struct Document {
+    parent: Option<Parent>,
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    version: Option<String>,
+    properties: HashMap<String, String>,
+    dependencies: Vec<Dependency>,
+    managed_dependencies: Vec<Dependency>,
+}
+
+#[derive(Default)]
+struct Parent {
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    version: Option<String>,
+    relative_path: Option<String>,
+}
+
+#[derive(Default)]
+struct Dependency {
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    version: Option<String>,
+    scope: Option<String>,
+}
+
+impl SoupParse for Pom {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let document = parse(content)?;
+        let mut properties = HashMap::new();
+        let mut managed_dependencies = HashMap::new();
+        let parents = self
+            .parent_poms
+            .iter()
+            .map(|parent_pom| parse(parent_pom))
+            .collect::<Result<Vec<Document>, SoupSourceParseError>>()?;
+        for parent in parents.iter().rev() {
+            inherit(parent, &mut properties, &mut managed_dependencies);
+        }
+        inherit(&document, &mut properties, &mut managed_dependencies);
+
+        let mut soups = BTreeSet::new();
+        for dependency in &document.dependencies {
+            let (group_id, artifact_id) = match (&dependency.group_id, &dependency.artifact_id) {
+                (Some(group_id), Some(artifact_id)) => (
+                    interpolate(group_id, &properties),
+                    interpolate(artifact_id, &properties),
+                ),
+                _ => {
+                    return Err(SoupSourceParseError {
+                        message: "Missing groupId or artifactId in dependency".to_owned(),
+                    })
+                }
+            };
+            let name = format!("{}:{}", group_id, artifact_id);
+            let managed = managed_dependencies.get(&name);
+            let scope = dependency
+                .scope
+                .as_ref()
+                .or_else(|| managed.and_then(|managed| managed.scope.as_ref()))
+                .map(|scope| interpolate(scope, &properties))
+                .unwrap_or_else(|| "compile".to_owned());
+            if scope == "test" {
+                continue;
+            }
+            let version = match dependency
+                .version
+                .as_ref()
+                .or_else(|| managed.and_then(|managed| managed.version.as_ref()))
+            {
+                Some(version) => interpolate(version, &properties),
+                None => "unknown".to_owned(),
+            };
+            let mut meta = default_meta.clone();
+            meta.insert("group".to_owned(), Value::from(group_id));
+            meta.insert("artifact".to_owned(), Value::from(artifact_id));
+            meta.insert("scope".to_owned(), Value::from(scope));
+            soups.insert(Soup {
+                name,
+                version,
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// Returns the path of the parent POM relative to the directory of the given POM, or `None`
+/// if it has no parent or the parent should not be looked up in the repository.
+pub fn parent_relative_path(content: &str) -> Option<String> {
+    let parent = parse(content).ok()?.parent?;
+    match parent.relative_path {
+        Some(relative_path) if relative_path.is_empty() => None,
+        Some(relative_path) => Some(relative_path),
+        None => Some("../pom.xml".to_owned()),
+    }
+}
+
+/// Whether the `<parent>` of a POM refers to the given parent POM by groupId, artifactId and
+/// version. A parent POM may inherit its own groupId and version from its parent.
+pub fn is_parent(content: &str, parent_content: &str) -> bool {
+    let parent = match parse(content).ok().and_then(|document| document.parent) {
+        Some(parent) => parent,
+        None => return false,
+    };
+    let document = match parse(parent_content) {
+        Ok(document) => document,
+        Err(_e) => return false,
+    };
+    let grandparent = document.parent.as_ref();
+    let group_id = document
+        .group_id
+        .as_ref()
+        .or_else(|| grandparent.and_then(|grandparent| grandparent.group_id.as_ref()));
+    let version = document
+        .version
+        .as_ref()
+        .or_else(|| grandparent.and_then(|grandparent| grandparent.version.as_ref()));
+    parent.group_id.is_some()
+        && parent.artifact_id.is_some()
+        && parent.version.is_some()
+        && parent.group_id.as_ref() == group_id
+        && parent.artifact_id == document.artifact_id
+        && parent.version.as_ref() == version
+}
+
+/// Adds the properties and managed dependencies of a POM, overriding those inherited so far.
+fn inherit<'a>(
+    document: &'a Document,
+    properties: &mut HashMap<String, String>,
+    managed_dependencies: &mut HashMap<String, &'a Dependency>,
+) {
+    let parent = document.parent.as_ref();
+    let group_id = document
+        .group_id
+        .as_ref()
+        .or_else(|| parent.and_then(|parent| parent.group_id.as_ref()));
+    let version = document
+        .version
+        .as_ref()
+        .or_else(|| parent.and_then(|parent| parent.version.as_ref()));
+    let builtins = [
+        ("project.groupId", group_id),
+        ("project.version", version),
+        ("pom.version", version),
+        (
+            "project.parent.groupId",
+            parent.and_then(|parent| parent.group_id.as_ref()),
+        ),
+        (
+            "project.parent.version",
+            parent.and_then(|parent| parent.version.as_ref()),
+        ),
+    ];
+    for (key, value) in builtins {
+        if let Some(value) = value {
+            properties.insert(key.to_owned(), value.to_owned());
+        }
+    }
+    properties.extend(
+        document
+            .properties
+            .iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    for dependency in &document.managed_dependencies {
+        if let (Some(group_id), Some(artifact_id)) = (&dependency.group_id, &dependency.artifact_id)
+        {
+            let name = format!(
+                "{}:{}",
+                interpolate(group_id, properties),
+                interpolate(artifact_id, properties)
+            );
+            managed_dependencies.insert(name, dependency);
+        }
+    }
+}
+
+/// Replaces `${property}` references, leaving unknown properties as they are.
+fn interpolate(value: &str, properties: &HashMap<String, String>) -> String {
+    let mut value = value.to_owned();
+    for _ in 0..MAX_INTERPOLATION_DEPTH {
+        if !PROPERTY.is_match(&value) {
+            break;
+        }
+        let interpolated = PROPERTY
+            .replace_all(&value, |captures: &Captures| {
+                match properties.get(&captures["name"]) {
+                    Some(property) => property.to_owned(),
+                    None => captures[0].to_owned(),
+                }
+            })
+            .into_owned();
+        if interpolated == value {
+            break;
+        }
+        value = interpolated;
+    }
+    value
+}
+
+fn parse(content: &str) -> Result<Document, SoupSourceParseError> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+
+    let mut document = Document::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.name()).into_owned();
+                path.push(name);
+                match path_str(&path).as_slice() {
+                    ["project", "parent"] => document.parent = Some(Parent::default()),
+                    ["project", "dependencies", "dependency"] => {
+                        document.dependencies.push(Dependency::default())
+                    }
+                    ["project", "dependencyManagement", "dependencies", "dependency"] => {
+                        document.managed_dependencies.push(Dependency::default())
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => {
+                if let (["project", "parent", "relativePath"], Some(parent)) =
+                    (path_str(&path).as_slice(), &mut document.parent)
+                {
+                    parent.relative_path.get_or_insert_with(String::new);
+                }
+                path.pop();
+            }
+            Ok(Event::Text(ref e)) => {
+                let text = match e.unescape_and_decode(&reader) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        return Err(SoupSourceParseError {
+                            message: format!("Invalid XML structure {}", e),
+                        });
+                    }
+                };
+                set_text(&mut document, &path_str(&path), text);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid XML structure {}", e),
+                });
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(document)
+}
+
+fn path_str(path: &[String]) -> Vec<&str> {
+    path.iter().map(String::as_str).collect()
+}
+
+fn set_text(document: &mut Document, path: &[&str], text: String) {
+    match path {
+        ["project", "groupId"] => document.group_id = Some(text),
+        ["project", "artifactId"] => document.artifact_id = Some(text),
+        ["project", "version"] => document.version = Some(text),
+        ["project", "properties", property] => {
+            document.properties.insert(property.to_string(), text);
+        }
+        ["project", "parent", field] => {
+            if let Some(parent) = &mut document.parent {
+                match *field {
+                    "groupId" => parent.group_id = Some(text),
+                    "artifactId" => parent.artifact_id = Some(text),
+                    "version" => parent.version = Some(text),
+                    "relativePath" => parent.relative_path = Some(text),
+                    _ => {}
+                }
+            }
+        }
+        ["project", "dependencies", "dependency", field] => {
+            if let Some(dependency) = document.dependencies.last_mut() {
+                set_dependency_field(dependency, field, text);
+            }
+        }
+        ["project", "dependencyManagement", "dependencies", "dependency", field] => {
+            if let Some(dependency) = document.managed_dependencies.last_mut() {
+                set_dependency_field(dependency, field, text);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn set_dependency_field(dependency: &mut Dependency, field: &str, text: String) {
+    match field {
+        "groupId" => dependency.group_id = Some(text),
+        "artifactId" => dependency.artifact_id = Some(text),
+        "version" => dependency.version = Some(text),
+        "scope" => dependency.scope = Some(text),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    const PARENT_POM: &str = r#"
+<project>
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.2.0</version>
+    <packaging>pom</packaging>
+    <properties>
+        <jackson.version>2.13.4</jackson.version>
+        <slf4j.version>1.7.36</slf4j.version>
+    </properties>
+    <dependencyManagement>
+        <dependencies>
+            <dependency>
+                <groupId>com.fasterxml.jackson.core</groupId>
+                <artifactId>jackson-databind</artifactId>
+                <version>${jackson.version}</version>
+            </dependency>
+            <dependency>
+                <groupId>junit</groupId>
+                <artifactId>junit</artifactId>
+                <version>4.13.2</version>
+                <scope>test</scope>
+            </dependency>
+        </dependencies>
+    </dependencyManagement>
+</project>
+    "#;
+
+    const POM: &str = r#"
+<project>
+    <modelVersion>4.0.0</modelVersion>
+    <parent>
+        <groupId>com.example</groupId>
+        <artifactId>parent</artifactId>
+        <version>1.2.0</version>
+    </parent>
+    <artifactId>service</artifactId>
+    <properties>
+        <slf4j.version>2.0.3</slf4j.version>
+    </properties>
+    <dependencies>
+        <dependency>
+            <groupId>com.fasterxml.jackson.core</groupId>
+            <artifactId>jackson-databind</artifactId>
+        </dependency>
+        <dependency>
+            <groupId>org.slf4j</groupId>
+            <artifactId>slf4j-api</artifactId>
+            <version>${slf4j.version}</version>
+        </dependency>
+        <dependency>
+            <groupId>${project.groupId}</groupId>
+            <artifactId>common</artifactId>
+            <version>${project.version}</version>
+            <scope>provided</scope>
+        </dependency>
+        <dependency>
+            <groupId>junit</groupId>
+            <artifactId>junit</artifactId>
+        </dependency>
+    </dependencies>
+    <build>
+        <plugins>
+            <plugin>
+                <groupId>org.apache.maven.plugins</groupId>
+                <artifactId>maven-compiler-plugin</artifactId>
+                <version>3.10.1</version>
+                <dependencies>
+                    <dependency>
+                        <groupId>org.ow2.asm</groupId>
+                        <artifactId>asm</artifactId>
+                        <version>9.4</version>
+                    </dependency>
+                </dependencies>
+            </plugin>
+        </plugins>
+    </build>
+</project>
+    "#;
+
+    #[test]
+    fn dependencies_with_parent() {
+        let result = Pom {
+            parent_poms: vec![PARENT_POM.to_owned()],
+        }
+        .soups(POM, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                soup("com.example:common", "1.2.0"),
+                soup("com.fasterxml.jackson.core:jackson-databind", "2.13.4"),
+                soup("org.slf4j:slf4j-api", "2.0.3"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let scope = |name: &str| {
+            soups
+                .iter()
+                .find(|soup| soup.name == name)
+                .and_then(|soup| soup.meta.get("scope"))
+                .cloned()
+        };
+        assert_eq!(Some(Value::from("provided")), scope("com.example:common"));
+        assert_eq!(Some(Value::from("compile")), scope("org.slf4j:slf4j-api"));
+    }
+
+    #[test]
+    fn dependencies_without_parent() {
+        let result = Pom {
+            parent_poms: Vec::new(),
+        }
+        .soups(POM, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            true,
+            soups.contains(&soup(
+                "com.fasterxml.jackson.core:jackson-databind",
+                "unknown"
+            ))
+        );
+        assert_eq!(true, soups.contains(&soup("junit:junit", "unknown")));
+    }
+
+    #[test_case(
+        "<project><parent><artifactId>parent</artifactId></parent></project>",
+        Some("../pom.xml")
+    )]
+    #[test_case(
+        "<project><parent><relativePath>../base/pom.xml</relativePath></parent></project>",
+        Some("../base/pom.xml")
+    )]
+    #[test_case("<project><parent><relativePath/></parent></project>", None)]
+    #[test_case("<project></project>", None)]
+    fn parent_path(input: &str, expected: Option<&str>) {
+        assert_eq!(expected.map(str::to_owned), parent_relative_path(input));
+    }
+
+    #[test_case(
+        "<project><groupId>com.example</groupId><artifactId>parent</artifactId><version>1.0</version></project>",
+        true
+    )]
+    #[test_case(
+        "<project><parent><groupId>com.example</groupId><artifactId>root</artifactId><version>1.0</version></parent><artifactId>parent</artifactId></project>",
+        true; "inherited coordinates"
+    )]
+    #[test_case(
+        "<project><groupId>com.example</groupId><artifactId>other</artifactId><version>1.0</version></project>",
+        false
+    )]
+    #[test_case(
+        "<project><groupId>com.example</groupId><artifactId>parent</artifactId><version>2.0</version></project>",
+        false; "other version"
+    )]
+    fn parent_coordinates(parent_content: &str, expected: bool) {
+        let content = "<project><parent><groupId>com.example</groupId><artifactId>parent</artifactId><version>1.0</version></parent></project>";
+        assert_eq!(expected, is_parent(content, parent_content));
+    }
+
+    #[test]
+    fn missing_artifact_id() {
+        let content = "<project><dependencies><dependency><groupId>junit</groupId></dependency></dependencies></project>";
+        let result = Pom {
+            parent_poms: Vec::new(),
+        }
+        .soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
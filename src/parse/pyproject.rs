@@ -0,0 +1,253 @@
+use super::{requirements, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+pub struct PyProject {}
+
+#[derive(Deserialize)]
+struct Content {
+    project: Option<Project>,
+    tool: Option<Tool>,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    dependencies: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct Tool {
+    poetry: Option<Poetry>,
+}
+
+#[derive(Deserialize)]
+struct Poetry {
+    dependencies: Option<HashMap<String, toml::Value>>,
+}
+
+impl SoupParse for PyProject {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content = parse(content)?;
+        let mut soups = BTreeSet::new();
+        for requirement in content
+            .project
+            .and_then(|project| project.dependencies)
+            .unwrap_or_default()
+        {
+            if let Some(soup) = requirements::parse_requirement(&requirement, default_meta)? {
+                soups.insert(soup);
+            }
+        }
+        let poetry_dependencies = content
+            .tool
+            .and_then(|tool| tool.poetry)
+            .and_then(|poetry| poetry.dependencies)
+            .unwrap_or_default();
+        for (name, value) in poetry_dependencies {
+            if name == "python" {
+                continue;
+            }
+            if let Some(version) = poetry_version(&name, &value)? {
+                soups.insert(Soup {
+                    name,
+                    version,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+/// Returns the normalized names of all dependencies declared in pyproject.toml.
+pub fn dependency_names(content: &str) -> Result<HashSet<String>, SoupSourceParseError> {
+    let content = parse(content)?;
+    let mut names = HashSet::new();
+    for requirement in content
+        .project
+        .and_then(|project| project.dependencies)
+        .unwrap_or_default()
+    {
+        if let Some(soup) = requirements::parse_requirement(&requirement, &Map::new())? {
+            names.insert(requirements::normalize_name(&soup.name));
+        }
+    }
+    let poetry_dependencies = content
+        .tool
+        .and_then(|tool| tool.poetry)
+        .and_then(|poetry| poetry.dependencies)
+        .unwrap_or_default();
+    names.extend(
+        poetry_dependencies
+            .keys()
+            .map(|name| requirements::normalize_name(name)),
+    );
+    Ok(names)
+}
+
+fn parse(content: &str) -> Result<Content, SoupSourceParseError> {
+    match toml::from_str(content) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid pyproject.toml ({})", e),
+        }),
+    }
+}
+
+/// Poetry dependencies are given as a constraint, a list of constraints, or a table with a
+/// `version`, a `git` repository, a `url` or a `path`. Git dependencies get their `rev`, `tag`
+/// or `branch` as version, and path dependencies have none.
+fn poetry_version(
+    dependency: &str,
+    value: &toml::Value,
+) -> Result<Option<String>, SoupSourceParseError> {
+    let table = match value {
+        toml::Value::String(version) => return Ok(Some(version.to_owned())),
+        toml::Value::Array(constraints) => {
+            return match constraints.first() {
+                Some(constraint) => poetry_version(dependency, constraint),
+                None => Err(SoupSourceParseError {
+                    message: format!("Missing version for: {}", dependency),
+                }),
+            }
+        }
+        toml::Value::Table(table) => table,
+        _ => {
+            return Err(SoupSourceParseError {
+                message: format!("Malformed Poetry dependency: {}", dependency),
+            })
+        }
+    };
+    if table.contains_key("path") {
+        return Ok(None);
+    }
+    if table.contains_key("git") {
+        let reference = ["rev", "tag", "branch"]
+            .iter()
+            .find_map(|key| table.get(*key).and_then(toml::Value::as_str));
+        return Ok(Some(reference.unwrap_or("unknown").to_owned()));
+    }
+    match table.get("url").or_else(|| table.get("version")) {
+        Some(version) => match version.as_str() {
+            Some(version) => Ok(Some(version.to_owned())),
+            None => Err(SoupSourceParseError {
+                message: format!("Invalid version for: {}", dependency),
+            }),
+        },
+        None => Err(SoupSourceParseError {
+            message: format!("Missing version for: {}", dependency),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn project_dependencies() {
+        let input = r#"
+[project]
+name = "some-app"
+dependencies = [
+    "requests==2.28.1",
+    "importlib-metadata>=4.0; python_version < '3.8'",
+]
+        "#;
+        let result = PyProject {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "importlib-metadata".to_owned(),
+                    version: ">=4.0".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "requests".to_owned(),
+                    version: "2.28.1".to_owned(),
+                    meta: Map::new()
+                }
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test]
+    fn poetry_dependencies() {
+        let input = r#"
+[tool.poetry.dependencies]
+python = "^3.9"
+requests = "^2.28"
+flask = { version = "2.2.2", extras = ["async"] }
+some-lib = { git = "https://github.com/some/lib.git", tag = "v1.0.0" }
+internal-lib = { path = "../internal-lib" }
+wheel-lib = { url = "https://example.com/wheel_lib-1.0-py3-none-any.whl" }
+numpy = [{ version = "1.23.0", python = "<3.8" }, { version = "1.24.0", python = ">=3.8" }]
+
+[tool.poetry.group.dev.dependencies]
+pytest = "^7.1"
+        "#;
+        let result = PyProject {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "flask".to_owned(),
+                    version: "2.2.2".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "numpy".to_owned(),
+                    version: "1.23.0".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "requests".to_owned(),
+                    version: "^2.28".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "some-lib".to_owned(),
+                    version: "v1.0.0".to_owned(),
+                    meta: Map::new()
+                },
+                Soup {
+                    name: "wheel-lib".to_owned(),
+                    version: "https://example.com/wheel_lib-1.0-py3-none-any.whl".to_owned(),
+                    meta: Map::new()
+                }
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test_case("")]
+    #[test_case("[build-system]\nrequires = [\"setuptools\"]")]
+    fn no_dependencies(input: &str) {
+        let result = PyProject {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test_case("[tool.poetry.dependencies]\nrequests = 2")]
+    #[test_case("[tool.poetry.dependencies]\nrequests = { optional = true }")]
+    #[test_case("[project]\ndependencies = \"requests\"")]
+    fn invalid_dependencies(input: &str) {
+        let result = PyProject {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
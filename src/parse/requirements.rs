@@ -0,0 +1,366 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+pub struct Requirements {
+    /// Content of the files included with `-r`, read recursively
+    pub included_requirements: Vec<String>,
+}
+
+lazy_static! {
+    static ref LINE_CONTINUATION: Regex = Regex::new(r"\\\r?\n").unwrap();
+    static ref COMMENT: Regex = Regex::new(r"(?:^|\s+)#.*$").unwrap();
+    static ref REQUIREMENT: Regex = Regex::new(
+        r"^(?P<name>[A-Za-z0-9][A-Za-z0-9\._\-]*)\s*(?:\[[^\]]*\])?\s*(?:@\s*(?P<url>\S+)|(?P<specifier>[^@]*))$"
+    )
+    .unwrap();
+    static ref URL_OR_PATH: Regex = Regex::new(
+        r"^(?:[A-Za-z][A-Za-z0-9+.\-]*://|file:|[A-Za-z]:[\\/]|[./\\~]|[^\s@]*\.(?:whl|zip|tar\.gz|tar\.bz2|tgz)(?:#.*)?$)"
+    )
+    .unwrap();
+    static ref EGG: Regex = Regex::new(r"#(?:.*&)?egg=(?P<name>[A-Za-z0-9][A-Za-z0-9\._\-]*)").unwrap();
+    static ref HASH: Regex = Regex::new(r"--hash[=\s]+(?P<hash>\S+)").unwrap();
+}
+
+impl SoupParse for Requirements {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut soups = parse_requirements(content, default_meta)?;
+        for included_requirements in &self.included_requirements {
+            soups.extend(parse_requirements(included_requirements, default_meta)?);
+        }
+        Ok(soups)
+    }
+}
+
+fn parse_requirements(
+    content: &str,
+    default_meta: &Map<String, Value>,
+) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+    let mut soups = BTreeSet::new();
+    for line in lines(content) {
+        let editable = line
+            .strip_prefix("--editable")
+            .or_else(|| line.strip_prefix("-e"))
+            .map(|rest| rest.strip_prefix('=').unwrap_or(rest).trim());
+        let soup = match editable {
+            Some(editable) => parse_url_or_path(editable, None, vec![], default_meta),
+            None if line.starts_with('-') => continue,
+            None => parse_requirement(&line, default_meta)?,
+        };
+        if let Some(soup) = soup {
+            soups.insert(soup);
+        }
+    }
+    Ok(soups)
+}
+
+/// Joins continued lines and strips comments, leaving only non-empty lines.
+fn lines(content: &str) -> Vec<String> {
+    LINE_CONTINUATION
+        .replace_all(content, "")
+        .lines()
+        .map(|line| COMMENT.replace(line, "").trim().to_owned())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Returns the files included with `-r` or `--requirement`, relative to the including file.
+pub fn included_files(content: &str) -> Vec<String> {
+    lines(content)
+        .iter()
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix("--requirement")
+                .or_else(|| line.strip_prefix("-r"))?;
+            let rest = rest.strip_prefix('=').unwrap_or(rest).trim();
+            match rest.is_empty() {
+                true => None,
+                false => Some(rest.to_owned()),
+            }
+        })
+        .collect()
+}
+
+/// URL, VCS and path requirements are only recorded when they name the package with `#egg=`.
+pub fn parse_requirement(
+    line: &str,
+    default_meta: &Map<String, Value>,
+) -> Result<Option<Soup>, SoupSourceParseError> {
+    let hashes = HASH
+        .captures_iter(line)
+        .filter_map(|captures| captures.name("hash"))
+        .map(|hash| Value::from(hash.as_str()))
+        .collect::<Vec<Value>>();
+    let requirement = match line.find(" --") {
+        Some(index) => &line[..index],
+        None => line,
+    };
+    let (requirement, marker) = match requirement.split_once(';') {
+        Some((requirement, marker)) => (requirement.trim(), Some(marker.trim())),
+        None => (requirement.trim(), None),
+    };
+    if URL_OR_PATH.is_match(requirement) {
+        return Ok(parse_url_or_path(requirement, marker, hashes, default_meta));
+    }
+    let captures = match REQUIREMENT.captures(requirement) {
+        Some(captures) => captures,
+        None => {
+            return Err(SoupSourceParseError {
+                message: format!("Unable to parse requirement: {}", line),
+            })
+        }
+    };
+    let name = match captures.name("name") {
+        Some(name) => name.as_str().to_owned(),
+        None => {
+            return Err(SoupSourceParseError {
+                message: format!("Unable to parse requirement: {}", line),
+            })
+        }
+    };
+    let version = match (captures.name("url"), captures.name("specifier")) {
+        (Some(url), _) => url.as_str().to_owned(),
+        (None, Some(specifier)) => version(specifier.as_str()),
+        (None, None) => "unknown".to_owned(),
+    };
+    let soup = requirement_soup(name, version, marker, hashes, default_meta);
+    Ok(Some(soup))
+}
+
+/// The package is named by `#egg=`, and the URL or path without its fragment is the version.
+fn parse_url_or_path(
+    requirement: &str,
+    marker: Option<&str>,
+    hashes: Vec<Value>,
+    default_meta: &Map<String, Value>,
+) -> Option<Soup> {
+    let name = EGG.captures(requirement)?["name"].to_owned();
+    let version = match requirement.split_once('#') {
+        Some((url, _fragment)) => url.to_owned(),
+        None => requirement.to_owned(),
+    };
+    Some(requirement_soup(
+        name,
+        version,
+        marker,
+        hashes,
+        default_meta,
+    ))
+}
+
+fn requirement_soup(
+    name: String,
+    version: String,
+    marker: Option<&str>,
+    hashes: Vec<Value>,
+    default_meta: &Map<String, Value>,
+) -> Soup {
+    let mut meta = default_meta.clone();
+    if let Some(marker) = marker {
+        meta.insert("environment-marker".to_owned(), Value::from(marker));
+    }
+    if !hashes.is_empty() {
+        meta.insert("hashes".to_owned(), Value::from(hashes));
+    }
+    Soup {
+        name,
+        version,
+        meta,
+    }
+}
+
+/// Normalizes a package name as described in PEP 503, for comparing names between files.
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// An exact pin (`==1.2.3`) is recorded as `1.2.3`, any other specifier as written.
+fn version(specifier: &str) -> String {
+    let specifier = specifier
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '(' && *c != ')')
+        .collect::<String>();
+    if specifier.is_empty() {
+        return "unknown".to_owned();
+    }
+    match specifier
+        .strip_prefix("===")
+        .or_else(|| specifier.strip_prefix("=="))
+    {
+        Some(version) if !version.contains(',') && !version.contains('*') => version.to_owned(),
+        _ => specifier,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn soups(input: &str) -> BTreeSet<Soup> {
+        let result = Requirements {
+            included_requirements: vec![],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        result.unwrap()
+    }
+
+    #[test_case("requests==2.28.1", "2.28.1")]
+    #[test_case("requests === 2.28.1", "2.28.1"; "arbitrary equality")]
+    #[test_case("requests~=2.28", "~=2.28")]
+    #[test_case("requests>=2.28.0", ">=2.28.0")]
+    #[test_case("requests >= 2.28.0, < 3", ">=2.28.0,<3")]
+    #[test_case("requests==2.*", "==2.*")]
+    #[test_case("requests", "unknown")]
+    #[test_case("requests[security,socks]==2.28.1", "2.28.1")]
+    #[test_case("requests==2.28.1 # Some comment", "2.28.1")]
+    #[test_case(
+        "requests @ https://example.com/requests-2.28.1.tar.gz",
+        "https://example.com/requests-2.28.1.tar.gz"
+    )]
+    fn specifier(input: &str, expected_version: &str) {
+        let soups = soups(input);
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("requests", soup.name);
+        assert_eq!(expected_version, soup.version);
+    }
+
+    #[test]
+    fn environment_marker() {
+        let soups = soups(r#"importlib-metadata==4.12.0; python_version < "3.8""#);
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("importlib-metadata", soup.name);
+        assert_eq!("4.12.0", soup.version);
+        assert_eq!(
+            Some(&Value::from(r#"python_version < "3.8""#)),
+            soup.meta.get("environment-marker")
+        );
+    }
+
+    #[test]
+    fn hashes() {
+        let input = "requests==2.28.1 \\\n    --hash=sha256:7c5599b102feddaa661c826c56ab4fee28bfd17f5abca1ebbe3e7f19d7c97983 \\\n    --hash=sha256:8fefa2a1a1365bf5520aac41836fbee479da67864514bdb821f31ce07ce65349\n";
+        let soups = soups(input);
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("2.28.1", soup.version);
+        assert_eq!(
+            Some(&serde_json::json!([
+                "sha256:7c5599b102feddaa661c826c56ab4fee28bfd17f5abca1ebbe3e7f19d7c97983",
+                "sha256:8fefa2a1a1365bf5520aac41836fbee479da67864514bdb821f31ce07ce65349"
+            ])),
+            soup.meta.get("hashes")
+        );
+    }
+
+    #[test]
+    fn options_and_comments() {
+        let input = r#"
+# Some comment
+--index-url https://pypi.org/simple
+-c constraints.txt
+-e git+https://github.com/some/lib.git#egg=some-lib
+flask==2.2.2
+        "#;
+        let soups = soups(input);
+        assert_eq!(
+            vec!["flask", "some-lib"],
+            soups
+                .iter()
+                .map(|soup| soup.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test_case(
+        "-e git+https://github.com/some/lib.git@v1.2#egg=lib",
+        Some("git+https://github.com/some/lib.git@v1.2")
+    )]
+    #[test_case(
+        "--editable=git+https://github.com/some/lib.git@v1.2#egg=lib",
+        Some("git+https://github.com/some/lib.git@v1.2")
+    )]
+    #[test_case("-e src/lib#egg=lib", Some("src/lib"))]
+    #[test_case("-e .", None)]
+    #[test_case("--editable ./src/lib", None)]
+    fn editable(input: &str, expected_version: Option<&str>) {
+        let soups = soups(input);
+        assert_eq!(
+            expected_version,
+            soups.iter().next().map(|soup| soup.version.as_str())
+        );
+    }
+
+    #[test_case(
+        "git+https://github.com/foo/bar.git@v1.0#egg=bar",
+        "git+https://github.com/foo/bar.git@v1.0"
+    )]
+    #[test_case(
+        "https://example.com/bar-1.0.tar.gz#sha256=abc&egg=bar",
+        "https://example.com/bar-1.0.tar.gz"
+    )]
+    #[test_case("./vendor/bar-1.0.whl#egg=bar", "./vendor/bar-1.0.whl")]
+    fn url_and_path_with_egg(input: &str, expected_version: &str) {
+        let soups = soups(input);
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("bar", soup.name);
+        assert_eq!(expected_version, soup.version);
+    }
+
+    #[test_case("git+https://github.com/foo/bar.git@v1.0")]
+    #[test_case("https://example.com/pkg-1.0.tar.gz")]
+    #[test_case("./vendor/pkg-1.0.whl")]
+    #[test_case("../pkg")]
+    #[test_case("/opt/pkg")]
+    #[test_case(".")]
+    #[test_case("pkg-1.0-py3-none-any.whl")]
+    #[test_case("file:///opt/pkg-1.0.tar.gz")]
+    fn url_and_path_without_egg(input: &str) {
+        assert_eq!(0, soups(input).len());
+    }
+
+    #[test]
+    fn included_requirements() {
+        let input = "--requirement=base.txt\nrequests==2.28.1\n";
+        let result = Requirements {
+            included_requirements: vec!["flask==2.2.2\n-r requirements.txt\n".to_owned()],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let names = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| soup.name)
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["flask", "requests"], names);
+    }
+
+    #[test]
+    fn included_files() {
+        let input = "-r base.txt\n--requirement=dev.txt\n-rtest.txt\n-c constraints.txt\nflask\n";
+        assert_eq!(
+            vec!["base.txt", "dev.txt", "test.txt"],
+            super::included_files(input)
+        );
+    }
+
+    #[test_case("==1.0.0")]
+    #[test_case("[extra]")]
+    fn invalid_requirement(input: &str) {
+        let result = Requirements {
+            included_requirements: vec![],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use super::{shell, SoupParse};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Packages installed with yum, dnf, microdnf or zypper.
+pub struct Rpm {}
+
+lazy_static! {
+    static ref VERSION_RELEASE: Regex =
+        Regex::new(r"^(?:\d+:)?\d[A-Za-z0-9\.+~_]*(?:-\d[A-Za-z0-9\.+~_]*)?$").unwrap();
+}
+
+const PROGRAMS: [&str; 5] = ["yum", "dnf", "microdnf", "tdnf", "zypper"];
+
+const OPTIONS_WITH_VALUE: [&str; 14] = [
+    "-c",
+    "--config",
+    "-x",
+    "--exclude",
+    "--enablerepo",
+    "--disablerepo",
+    "--repo",
+    "--installroot",
+    "--releasever",
+    "-r",
+    "--from",
+    "-t",
+    "--type",
+    "--root",
+];
+
+impl SoupParse for Rpm {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        let content = shell::normalize(content);
+        for line in content.lines() {
+            for command in shell::commands(line) {
+                let packages = match shell::subcommand_arguments(
+                    &command,
+                    &PROGRAMS,
+                    &["install", "in"],
+                    &OPTIONS_WITH_VALUE,
+                ) {
+                    Some(packages) => packages,
+                    None => continue,
+                };
+                for package in packages {
+                    if let Some(soup) = package_soup(package, default_meta) {
+                        result.insert(soup);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Packages are given as `name`, `name-version[-release]` or, for zypper, with a constraint
+/// such as `name=version` or `name>=version`. Groups, globs and package files are left out.
+fn package_soup(package: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    if package.starts_with(['$', '@'])
+        || package.contains(['*', '?', '/'])
+        || package.ends_with(".rpm")
+    {
+        return None;
+    }
+    let (name, version) = match package.find(['=', '<', '>']) {
+        Some(index) => {
+            let version = &package[index..];
+            (
+                &package[..index],
+                version.strip_prefix('=').unwrap_or(version),
+            )
+        }
+        None => split_name_version(package),
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some(Soup {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        meta: default_meta.clone(),
+    })
+}
+
+/// Names may contain dashes followed by digits, as in `java-11-openjdk`, so the version is
+/// the first suffix that looks like a version with an optional release.
+fn split_name_version(package: &str) -> (&str, &str) {
+    package
+        .match_indices('-')
+        .map(|(index, _)| (&package[..index], &package[index + 1..]))
+        .find(|(_, version)| VERSION_RELEASE.is_match(version))
+        .unwrap_or((package, "unknown"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test_case("yum install -y curl-7.61.1 git")]
+    #[test_case("dnf install -y --setopt=install_weak_deps=False curl-7.61.1 git")]
+    #[test_case("microdnf install --nodocs curl-7.61.1 git && microdnf clean all")]
+    #[test_case("zypper --non-interactive install curl=7.61.1 git")]
+    #[test_case("zypper -n in curl=7.61.1 git")]
+    fn package_managers(input: &str) {
+        let result = Rpm {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("curl", "7.61.1"), soup("git", "unknown")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case("curl", "curl", "unknown")]
+    #[test_case("python3-pip", "python3-pip", "unknown")]
+    #[test_case("java-11-openjdk", "java-11-openjdk", "unknown")]
+    #[test_case(
+        "java-11-openjdk-11.0.18.0.10-1.el8",
+        "java-11-openjdk",
+        "11.0.18.0.10-1.el8"
+    )]
+    #[test_case("libxml2-2.9.7-15.el8", "libxml2", "2.9.7-15.el8")]
+    #[test_case("shadow-utils-2:4.6-17.el8", "shadow-utils", "2:4.6-17.el8")]
+    #[test_case("curl>=7.61", "curl", ">=7.61")]
+    fn name_and_version(package: &str, name: &str, version: &str) {
+        let soup = package_soup(package, &Map::new()).unwrap();
+        assert_eq!(name, soup.name);
+        assert_eq!(version, soup.version);
+    }
+
+    #[test_case("yum install -y @development-tools")]
+    #[test_case("dnf install -y https://example.com/some-package.rpm")]
+    #[test_case("yum update -y")]
+    fn no_packages(input: &str) {
+        let result = Rpm {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+}
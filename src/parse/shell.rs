@@ -0,0 +1,196 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref LINE_CONTINUATION: Regex = Regex::new(r"\\[ \t]*(?:#.*)?\r?\n").unwrap();
+    static ref MULTI_SPACE: Regex = Regex::new(r"[ \t]+").unwrap();
+}
+
+/// Joins lines ending with a line continuation, so that each shell statement is on one line.
+pub fn normalize(input: &str) -> String {
+    let result = LINE_CONTINUATION.replace_all(input, " ");
+    let result = MULTI_SPACE.replace_all(&result, " ");
+    result.to_string()
+}
+
+/// Splits a line into commands separated by `&&`, `||`, `;` or `|`, each given as a list of
+/// words with quotes removed. Comments and redirections are left out.
+pub fn commands(line: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            match c == q {
+                true => quote = None,
+                false => word.get_or_insert_with(String::new).push(c),
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            '&' | '|' | ';' => {
+                words.extend(word.take());
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+                if chars.peek() == Some(&c) {
+                    chars.next();
+                }
+            }
+            '#' if word.is_none() => break,
+            '>' | '<' => {
+                // Drops the redirection, including a file descriptor before it and its target
+                word = None;
+                while matches!(chars.peek(), Some('>') | Some('&')) {
+                    chars.next();
+                }
+                while matches!(chars.peek(), Some(next) if next.is_whitespace()) {
+                    chars.next();
+                }
+                while matches!(chars.peek(), Some(next) if !next.is_whitespace() && !matches!(next, '&' | '|' | ';'))
+                {
+                    chars.next();
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}
+
+/// Returns the arguments of a command when it runs one of the given programs with one of the
+/// given subcommands, such as `apt-get install`. Options are skipped, including the value of
+/// those listed in `options_with_value`.
+pub fn subcommand_arguments<'a>(
+    command: &'a [String],
+    programs: &[&str],
+    subcommands: &[&str],
+    options_with_value: &[&str],
+) -> Option<Vec<&'a str>> {
+    let program_index = command_word_index(command);
+    let program = command.get(program_index)?;
+    if !programs.contains(&program.rsplit('/').next().unwrap_or(program)) {
+        return None;
+    }
+    let mut subcommand = None;
+    let mut arguments = Vec::new();
+    let mut words = command[program_index + 1..].iter();
+    while let Some(word) = words.next() {
+        if word.starts_with('-') {
+            if options_with_value.contains(&word.as_str()) {
+                words.next();
+            }
+            continue;
+        }
+        match subcommand {
+            Some(_) => arguments.push(word.as_str()),
+            None => subcommand = Some(word.as_str()),
+        }
+    }
+    match subcommand {
+        Some(subcommand) if subcommands.contains(&subcommand) => Some(arguments),
+        _ => None,
+    }
+}
+
+/// Finds the word naming the program that a command runs, skipping a leading Dockerfile `RUN`
+/// with its flags, variable assignments, wrappers such as `sudo` or `env`, and `python -m`.
+fn command_word_index(command: &[String]) -> usize {
+    let mut index = 0;
+    if matches!(command.first(), Some(word) if word.eq_ignore_ascii_case("RUN")) {
+        index += 1;
+        while matches!(command.get(index), Some(word) if word.starts_with("--")) {
+            index += 1;
+        }
+    }
+    while let Some(word) = command.get(index) {
+        let program = word.rsplit('/').next().unwrap_or(word);
+        if is_assignment(word) {
+            index += 1;
+        } else if WRAPPERS.contains(&program) {
+            index += 1;
+            while let Some(option) = command.get(index).filter(|word| word.starts_with('-')) {
+                index += 1;
+                if program == "sudo" && SUDO_OPTIONS_WITH_VALUE.contains(&option.as_str()) {
+                    index += 1;
+                }
+            }
+        } else if program.starts_with("python")
+            && command.get(index + 1).map(String::as_str) == Some("-m")
+        {
+            return index + 2;
+        } else {
+            break;
+        }
+    }
+    index
+}
+
+const WRAPPERS: [&str; 6] = ["sudo", "env", "exec", "command", "nohup", "time"];
+
+const SUDO_OPTIONS_WITH_VALUE: [&str; 4] = ["-u", "-g", "-C", "-D"];
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("apt-get install \\\n  curl \\  \n  wget", "apt-get install curl wget")]
+    #[test_case("apt-get install \\\r\n  curl\r\nls", "apt-get install curl\r\nls")]
+    #[test_case(
+        "printf \"a\\n\" > /f && apt-get install -y curl wget",
+        "printf \"a\\n\" > /f && apt-get install -y curl wget"
+    )]
+    fn normalize_lines(input: &str, expected: &str) {
+        assert_eq!(expected, normalize(input));
+    }
+
+    #[test_case("RUN apt-get update && apt-get install -y curl", vec![vec!["RUN", "apt-get", "update"], vec!["apt-get", "install", "-y", "curl"]])]
+    #[test_case("echo 'a && b'; ls | wc -l", vec![vec!["echo", "a && b"], vec!["ls"], vec!["wc", "-l"]])]
+    #[test_case("apt-get install curl > /dev/null 2>&1 || true", vec![vec!["apt-get", "install", "curl"], vec!["true"]])]
+    #[test_case("apt-get install curl # comment", vec![vec!["apt-get", "install", "curl"]])]
+    fn split_commands(input: &str, expected: Vec<Vec<&str>>) {
+        assert_eq!(expected, commands(input));
+    }
+
+    #[test_case("sudo apt-get -y install curl", Some(vec!["curl"]))]
+    #[test_case("RUN --mount=type=cache,target=/var/cache/apt apt-get install curl", Some(vec!["curl"]))]
+    #[test_case("DEBIAN_FRONTEND=noninteractive apt-get install curl", Some(vec!["curl"]))]
+    #[test_case("sudo -u root env DEBIAN_FRONTEND=noninteractive apt-get install curl", Some(vec!["curl"]))]
+    #[test_case("echo apt-get install curl", None)]
+    #[test_case("/usr/bin/apt-get install -o Dpkg::Options::=--force-confold curl", Some(vec!["curl"]))]
+    #[test_case("apt-get update", None)]
+    #[test_case("echo apt", None)]
+    fn arguments(input: &str, expected: Option<Vec<&str>>) {
+        let command = input
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            expected,
+            subcommand_arguments(&command, &["apt", "apt-get"], &["install"], &["-o"])
+        );
+    }
+}
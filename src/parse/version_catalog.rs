@@ -0,0 +1,164 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct VersionCatalog {}
+
+#[derive(Deserialize)]
+struct Content {
+    versions: Option<HashMap<String, toml::Value>>,
+    libraries: Option<HashMap<String, toml::Value>>,
+}
+
+impl SoupParse for VersionCatalog {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match toml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid version catalog ({})", e),
+                });
+            }
+        };
+        let versions = content.versions.unwrap_or_default();
+        let mut soups = BTreeSet::new();
+        for (alias, library) in content.libraries.unwrap_or_default() {
+            let (group, artifact, version) = library_coordinates(&alias, &library, &versions)?;
+            let mut meta = default_meta.clone();
+            meta.insert("group".to_owned(), Value::from(group.to_owned()));
+            meta.insert("artifact".to_owned(), Value::from(artifact.to_owned()));
+            soups.insert(Soup {
+                name: format!("{}:{}", group, artifact),
+                version: version.unwrap_or_else(|| "unknown".to_owned()),
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+fn library_coordinates(
+    alias: &str,
+    library: &toml::Value,
+    versions: &HashMap<String, toml::Value>,
+) -> Result<(String, String, Option<String>), SoupSourceParseError> {
+    let invalid = || SoupSourceParseError {
+        message: format!("Invalid library: {}", alias),
+    };
+    if let Some(notation) = library.as_str() {
+        let mut parts = notation.split(':');
+        return match (parts.next(), parts.next(), parts.next()) {
+            (Some(group), Some(artifact), version) => Ok((
+                group.to_owned(),
+                artifact.to_owned(),
+                version.map(str::to_owned),
+            )),
+            _ => Err(invalid()),
+        };
+    }
+    let (group, artifact) = match (
+        library.get("module").and_then(toml::Value::as_str),
+        library.get("group").and_then(toml::Value::as_str),
+        library.get("name").and_then(toml::Value::as_str),
+    ) {
+        (Some(module), _, _) => match module.split_once(':') {
+            Some((group, artifact)) => (group, artifact),
+            None => return Err(invalid()),
+        },
+        (None, Some(group), Some(artifact)) => (group, artifact),
+        _ => return Err(invalid()),
+    };
+    let version = match library.get("version") {
+        Some(toml::Value::Table(table)) => match table.get("ref").and_then(toml::Value::as_str) {
+            Some(reference) => match versions.get(reference) {
+                Some(version) => version_constraint(version),
+                None => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Missing version reference: {}", reference),
+                    })
+                }
+            },
+            None => version_constraint(&toml::Value::Table(table.to_owned())),
+        },
+        Some(version) => version_constraint(version),
+        None => None,
+    };
+    Ok((group.to_owned(), artifact.to_owned(), version))
+}
+
+/// A version is either a plain string or a rich version declaration, of which the most
+/// specific constraint is used.
+fn version_constraint(version: &toml::Value) -> Option<String> {
+    match version {
+        toml::Value::String(version) => Some(version.to_owned()),
+        toml::Value::Table(table) => ["strictly", "require", "prefer"]
+            .iter()
+            .find_map(|key| table.get(*key).and_then(toml::Value::as_str))
+            .map(str::to_owned),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    #[test]
+    fn libraries() {
+        let content = r#"
+[versions]
+okhttp = "4.10.0"
+jackson = { strictly = "2.13.4" }
+
+[libraries]
+guava = "com.google.guava:guava:31.1-jre"
+okhttp = { module = "com.squareup.okhttp3:okhttp", version.ref = "okhttp" }
+jackson-databind = { group = "com.fasterxml.jackson.core", name = "jackson-databind", version.ref = "jackson" }
+slf4j-api = { module = "org.slf4j:slf4j-api", version = { require = "2.0.3" } }
+spring-web = { module = "org.springframework:spring-web" }
+
+[plugins]
+kotlin-jvm = { id = "org.jetbrains.kotlin.jvm", version = "1.7.20" }
+        "#;
+        let result = VersionCatalog {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                soup("com.fasterxml.jackson.core:jackson-databind", "2.13.4"),
+                soup("com.google.guava:guava", "31.1-jre"),
+                soup("com.squareup.okhttp3:okhttp", "4.10.0"),
+                soup("org.slf4j:slf4j-api", "2.0.3"),
+                soup("org.springframework:spring-web", "unknown"),
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+        let guava = soups
+            .iter()
+            .find(|soup| soup.name == "com.google.guava:guava")
+            .unwrap();
+        assert_eq!(
+            Some(&Value::from("com.google.guava")),
+            guava.meta.get("group")
+        );
+        assert_eq!(Some(&Value::from("guava")), guava.meta.get("artifact"));
+    }
+
+    #[test_case("[libraries]\nguava = \"guava\"")]
+    #[test_case("[libraries]\nokhttp = { module = \"com.squareup.okhttp3:okhttp\", version.ref = \"okhttp\" }")]
+    #[test_case("[libraries")]
+    fn invalid_catalog(input: &str) {
+        let result = VersionCatalog {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use super::{
+    package_json::{self, DependencyKind},
+    SoupParse,
+};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+pub struct YarnLock {
+    pub include_transitive: bool,
+    pub kinds: Vec<DependencyKind>,
+    /// Content of the package.json next to the lock file, followed by those of its workspaces
+    pub package_jsons: Vec<String>,
+}
+
+struct Entry {
+    descriptors: Vec<(String, String)>,
+    version: String,
+    meta: Map<String, Value>,
+}
+
+impl SoupParse for YarnLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let entries = match content.contains("__metadata:") {
+            true => parse_berry(content)?,
+            false => parse_classic(content)?,
+        };
+        let direct_dependencies = self
+            .package_jsons
+            .iter()
+            .map(|package_json| package_json::dependencies(package_json, &self.kinds))
+            .collect::<Result<Vec<_>, SoupSourceParseError>>()?;
+        let mut soups = BTreeSet::new();
+        for entry in entries {
+            let direct_dependency = entry.descriptors.iter().find_map(|(name, range)| {
+                direct_dependencies.iter().find_map(|direct_dependencies| {
+                    let (direct_range, kind) = direct_dependencies.get(name)?;
+                    match direct_range == range || format!("npm:{}", direct_range) == *range {
+                        true => Some((name, kind)),
+                        false => None,
+                    }
+                })
+            });
+            let name = match direct_dependency {
+                Some((name, _)) => name,
+                None if !self.include_transitive => continue,
+                None => match entry.descriptors.first() {
+                    Some((name, _)) => name,
+                    None => continue,
+                },
+            };
+            let mut meta = default_meta.clone();
+            if let Some((_, kind)) = direct_dependency {
+                meta.insert("dependency-kind".to_owned(), Value::from(kind.name()));
+            }
+            meta.extend(entry.meta.clone());
+            soups.insert(Soup {
+                name: name.to_owned(),
+                version: entry.version.to_owned(),
+                meta,
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// Splits a descriptor such as `@scope/name@^1.0.0` into name and range.
+fn parse_descriptor(descriptor: &str) -> Option<(String, String)> {
+    let descriptor = descriptor.trim().trim_matches('"');
+    let separator = descriptor.get(1..)?.find('@')? + 1;
+    Some((
+        descriptor[..separator].to_owned(),
+        descriptor[separator + 1..].to_owned(),
+    ))
+}
+
+fn parse_descriptors(key: &str) -> Vec<(String, String)> {
+    key.split(", ").filter_map(parse_descriptor).collect()
+}
+
+fn parse_classic(content: &str) -> Result<Vec<Entry>, SoupSourceParseError> {
+    let mut blocks: Vec<(&str, HashMap<String, String>)> = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') || line.starts_with("    ") {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            blocks.push((line.trim_end().trim_end_matches(':'), HashMap::new()));
+            continue;
+        }
+        if let (Some((_, fields)), Some((key, value))) =
+            (blocks.last_mut(), line.trim().split_once(' '))
+        {
+            fields.insert(key.to_owned(), value.trim_matches('"').to_owned());
+        }
+    }
+    blocks
+        .into_iter()
+        .map(|(key, fields)| classic_entry(parse_descriptors(key), fields))
+        .collect()
+}
+
+fn classic_entry(
+    descriptors: Vec<(String, String)>,
+    mut fields: HashMap<String, String>,
+) -> Result<Entry, SoupSourceParseError> {
+    let version = match fields.remove("version") {
+        Some(version) => version,
+        None => {
+            return Err(SoupSourceParseError {
+                message: format!(
+                    "Missing version for: {}",
+                    descriptors
+                        .first()
+                        .map(|(name, _)| name.as_str())
+                        .unwrap_or_default()
+                ),
+            })
+        }
+    };
+    let meta = ["integrity", "resolved"]
+        .into_iter()
+        .filter_map(|key| Some((key.to_owned(), Value::from(fields.remove(key)?))))
+        .collect::<Map<String, Value>>();
+    Ok(Entry {
+        descriptors,
+        version,
+        meta,
+    })
+}
+
+fn parse_berry(content: &str) -> Result<Vec<Entry>, SoupSourceParseError> {
+    let content: HashMap<String, serde_yaml::Value> = match serde_yaml::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid yarn.lock structure ({})", e),
+            })
+        }
+    };
+    let mut entries = Vec::new();
+    for (key, value) in content {
+        if key == "__metadata" {
+            continue;
+        }
+        let resolution = value
+            .get("resolution")
+            .and_then(serde_yaml::Value::as_str)
+            .unwrap_or_default();
+        if resolution.contains("@workspace:")
+            || resolution.contains("@link:")
+            || resolution.contains("@portal:")
+        {
+            continue;
+        }
+        let version = match value.get("version") {
+            Some(serde_yaml::Value::String(version)) => version.to_owned(),
+            Some(serde_yaml::Value::Number(version)) => version.to_string(),
+            _ => {
+                return Err(SoupSourceParseError {
+                    message: format!("Missing version for: {}", key),
+                })
+            }
+        };
+        let meta = value
+            .get("checksum")
+            .and_then(serde_yaml::Value::as_str)
+            .map(|checksum| ("checksum".to_owned(), Value::from(checksum)))
+            .into_iter()
+            .collect::<Map<String, Value>>();
+        entries.push(Entry {
+            descriptors: parse_descriptors(&key),
+            version,
+            meta,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::soup;
+    use test_case::test_case;
+
+    const CLASSIC: &str = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.10.4":
+  version "7.12.13"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz#dcfc826beef65e75c50e21d3837d7d95798dd658"
+  integrity sha512-HV1Cm0Q3ZrpCR93tkWOYiuYIgLxZXZFVG2VgK+MBWjUqZTundupbfx2aXarXuw5Ko5aMcjtJgbSs4vUGBS5v6g==
+  dependencies:
+    "@babel/highlight" "^7.12.13"
+
+lodash@^4.17.20, lodash@^4.17.21:
+  version "4.17.21"
+  resolved "https://registry.yarnpkg.com/lodash/-/lodash-4.17.21.tgz#679591c564c3bffaae8454cf0b3df370c3d6911c"
+  integrity sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg==
+"#;
+
+    const BERRY: &str = r#"# This file is generated by running "yarn install" inside your project.
+# Manual changes might be lost - proceed with caution!
+
+__metadata:
+  version: 6
+  cacheKey: 8
+
+"@babel/code-frame@npm:^7.0.0, @babel/code-frame@npm:^7.10.4":
+  version: 7.12.13
+  resolution: "@babel/code-frame@npm:7.12.13"
+  dependencies:
+    "@babel/highlight": ^7.12.13
+  checksum: 471532bb7c
+  languageName: node
+  linkType: hard
+
+"lodash@npm:^4.17.20, lodash@npm:^4.17.21":
+  version: 4.17.21
+  resolution: "lodash@npm:4.17.21"
+  checksum: eb835a2e51
+  languageName: node
+  linkType: hard
+
+"some-app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "some-app@workspace:."
+  dependencies:
+    lodash: ^4.17.21
+  languageName: unknown
+  linkType: soft
+"#;
+
+    #[test_case(CLASSIC)]
+    #[test_case(BERRY)]
+    fn direct_dependencies(input: &str) {
+        let package_json = r#"{ "dependencies": { "lodash": "^4.17.21" } }"#;
+        let result = YarnLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons: vec![package_json.to_owned()],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![soup("lodash", "4.17.21")]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case(CLASSIC)]
+    #[test_case(BERRY)]
+    fn transitive_dependencies(input: &str) {
+        let result = YarnLock {
+            include_transitive: true,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons: vec![],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec![
+                soup("@babel/code-frame", "7.12.13"),
+                soup("lodash", "4.17.21")
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            result.unwrap()
+        );
+    }
+
+    #[test_case(CLASSIC)]
+    #[test_case(BERRY)]
+    fn workspace_dependencies(input: &str) {
+        let package_jsons = vec![
+            r#"{ "private": true, "workspaces": ["packages/*"] }"#.to_owned(),
+            r#"{ "dependencies": { "lodash": "^4.17.20" } }"#.to_owned(),
+            r#"{ "devDependencies": { "@babel/code-frame": "^7.0.0" } }"#.to_owned(),
+        ];
+        let result = YarnLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons,
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            vec!["lodash"],
+            result
+                .unwrap()
+                .into_iter()
+                .map(|soup| soup.name)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test_case(CLASSIC)]
+    #[test_case(BERRY)]
+    fn missing_package_json(input: &str) {
+        let result = YarnLock {
+            include_transitive: false,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons: vec![],
+        }
+        .soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+    }
+
+    #[test]
+    fn classic_meta() {
+        let result = YarnLock {
+            include_transitive: true,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons: vec![],
+        }
+        .soups(CLASSIC, &Map::new());
+        let soups = result.unwrap();
+        let lodash = soups.iter().find(|soup| soup.name == "lodash").unwrap();
+        assert_eq!(
+            Some(&Value::from("https://registry.yarnpkg.com/lodash/-/lodash-4.17.21.tgz#679591c564c3bffaae8454cf0b3df370c3d6911c")),
+            lodash.meta.get("resolved")
+        );
+        assert_eq!(true, lodash.meta.contains_key("integrity"));
+    }
+
+    #[test]
+    fn berry_invalid_yaml() {
+        let result = YarnLock {
+            include_transitive: true,
+            kinds: vec![DependencyKind::Normal],
+            package_jsons: vec![],
+        }
+        .soups("__metadata:\n  version: [6", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
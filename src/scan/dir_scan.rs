@@ -1,16 +1,45 @@
 use crate::{
     parse::{
-        apt::Apt, cargo::Cargo, csproj::CsProj, docker_base::DockerBase, package_json::PackageJson,
+        apk::Apk,
+        apt::Apt,
+        cargo::{self, Cargo},
+        cargo_lock::CargoLock,
+        csproj::CsProj,
+        docker_base::DockerBase,
+        docker_compose::DockerCompose,
+        dockerfile::Dockerfile,
+        gem::Gem,
+        go_mod::GoMod,
+        go_sum::GoSum,
+        gradle::Gradle,
+        helm_values::HelmValues,
+        kubernetes::{self, Kubernetes},
+        npm_global::NpmGlobal,
+        package_json::{self, PackageJson},
+        package_lock::PackageLock,
+        packages_config::PackagesConfig,
+        packages_lock::PackagesLock,
+        pip::Pip,
+        pipfile_lock::PipfileLock,
+        pnpm_lock::PnpmLock,
+        poetry_lock::PoetryLock,
+        pom::{self, Pom},
+        pyproject::PyProject,
+        requirements::{self, Requirements},
+        rpm::Rpm,
+        version_catalog::VersionCatalog,
+        yarn_lock::YarnLock,
         SoupParse,
     },
     soup::model::{Soup, SoupContexts, SouperIoError},
     utils,
 };
+use regex::Regex;
 use serde_json::{Map, Value};
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
-    io::Error,
+    io::{Error, ErrorKind},
     path::{Path, PathBuf},
 };
 
@@ -18,12 +47,20 @@ const GLOBAL_EXCLUDE_DIRS: [&str; 3] = ["node_modules", "bin", "obj"];
 
 pub type SoupParsers = Vec<Box<dyn SoupParse>>;
 
+pub struct ScanOptions {
+    pub include_transitive: bool,
+    pub cargo_kinds: Vec<cargo::DependencyKind>,
+    pub npm_kinds: Vec<package_json::DependencyKind>,
+    pub build_args: HashMap<String, String>,
+}
+
 pub fn scan(
     dir: &PathBuf,
     exclude_dirs: &Vec<PathBuf>,
     default_meta: Map<String, Value>,
+    options: &ScanOptions,
 ) -> Result<SoupContexts, SouperIoError> {
-    let path_parsers = match scan_dirs_recursively(dir, exclude_dirs) {
+    let path_parsers = match scan_dirs_recursively(dir, dir, exclude_dirs, options) {
         Ok(path_parsers) => path_parsers,
         Err(e) => {
             return Err(SouperIoError {
@@ -62,11 +99,13 @@ pub fn scan(
 }
 
 fn scan_dirs_recursively(
-    root: &PathBuf,
+    scan_root: &Path,
+    dir: &PathBuf,
     exclude_dirs: &Vec<PathBuf>,
+    options: &ScanOptions,
 ) -> Result<Vec<(PathBuf, SoupParsers)>, Error> {
     let mut sources: Vec<(PathBuf, Vec<Box<dyn SoupParse>>)> = Vec::new();
-    'entries: for entry in fs::read_dir(root)? {
+    'entries: for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
         let file_type = entry.file_type()?;
@@ -82,23 +121,179 @@ fn scan_dirs_recursively(
                     continue 'entries;
                 }
             }
-            let mut content = scan_dirs_recursively(&path, exclude_dirs)?;
+            let mut content = scan_dirs_recursively(scan_root, &path, exclude_dirs, options)?;
             sources.append(&mut content);
             continue;
         }
         if file_type.is_file() {
             match file_name.to_str() {
                 Some("package.json") => {
-                    sources.push((path, vec![Box::new(PackageJson {})]));
+                    sources.push((
+                        path,
+                        vec![Box::new(PackageJson {
+                            kinds: options.npm_kinds.to_owned(),
+                        })],
+                    ));
+                }
+                Some("package-lock.json") | Some("npm-shrinkwrap.json") => {
+                    let package_json = read_sibling_file(&path, "package.json")?;
+                    sources.push((
+                        path,
+                        vec![Box::new(PackageLock {
+                            include_transitive: options.include_transitive,
+                            kinds: options.npm_kinds.to_owned(),
+                            package_json,
+                        })],
+                    ));
+                }
+                Some("yarn.lock") => {
+                    let package_jsons = find_yarn_package_jsons(&path)?;
+                    sources.push((
+                        path,
+                        vec![Box::new(YarnLock {
+                            include_transitive: options.include_transitive,
+                            kinds: options.npm_kinds.to_owned(),
+                            package_jsons,
+                        })],
+                    ));
+                }
+                Some("pnpm-lock.yaml") => {
+                    sources.push((
+                        path,
+                        vec![Box::new(PnpmLock {
+                            include_transitive: options.include_transitive,
+                            kinds: options.npm_kinds.to_owned(),
+                        })],
+                    ));
                 }
                 Some("Cargo.toml") => {
-                    sources.push((path, vec![Box::new(Cargo {})]));
+                    let workspace_manifest = find_cargo_workspace_manifest(&path)?;
+                    sources.push((
+                        path,
+                        vec![Box::new(Cargo {
+                            kinds: options.cargo_kinds.to_owned(),
+                            workspace_manifest,
+                        })],
+                    ));
+                }
+                Some("Cargo.lock") => {
+                    let manifests = find_cargo_lock_manifests(&path)?;
+                    sources.push((
+                        path,
+                        vec![Box::new(CargoLock {
+                            include_transitive: options.include_transitive,
+                            kinds: options.cargo_kinds.to_owned(),
+                            manifests,
+                        })],
+                    ));
+                }
+                Some("go.mod") => {
+                    sources.push((path, vec![Box::new(GoMod {})]));
+                }
+                Some("go.sum") => {
+                    let go_mod = read_sibling_file(&path, "go.mod")?;
+                    sources.push((
+                        path,
+                        vec![Box::new(GoSum {
+                            include_transitive: options.include_transitive,
+                            go_mod,
+                        })],
+                    ));
+                }
+                Some("pom.xml") => {
+                    let parent_poms = find_maven_parent_poms(scan_root, &path)?;
+                    sources.push((path, vec![Box::new(Pom { parent_poms })]));
+                }
+                Some("build.gradle") | Some("build.gradle.kts") => {
+                    sources.push((path, vec![Box::new(Gradle {})]));
+                }
+                Some(file_name_str) if file_name_str.ends_with(".versions.toml") => {
+                    sources.push((path, vec![Box::new(VersionCatalog {})]));
                 }
-                Some(file_name_str) if file_name_str.contains(".csproj") => {
-                    sources.push((path, vec![Box::new(CsProj {})]));
+                Some("pyproject.toml") => {
+                    sources.push((path, vec![Box::new(PyProject {})]));
+                }
+                Some("poetry.lock") => {
+                    let pyproject = read_sibling_file(&path, "pyproject.toml")?;
+                    sources.push((
+                        path,
+                        vec![Box::new(PoetryLock {
+                            include_transitive: options.include_transitive,
+                            pyproject,
+                        })],
+                    ));
+                }
+                Some("Pipfile.lock") => {
+                    let pipfile = read_sibling_file(&path, "Pipfile")?;
+                    sources.push((
+                        path,
+                        vec![Box::new(PipfileLock {
+                            include_transitive: options.include_transitive,
+                            pipfile,
+                        })],
+                    ));
+                }
+                Some(file_name_str)
+                    if file_name_str.ends_with(".txt")
+                        && (file_name_str.starts_with("requirements")
+                            || file_name_str.starts_with("constraints")) =>
+                {
+                    let included_requirements = find_included_requirements(&path)?;
+                    sources.push((
+                        path,
+                        vec![Box::new(Requirements {
+                            included_requirements,
+                        })],
+                    ));
+                }
+                Some("packages.config") => {
+                    sources.push((path, vec![Box::new(PackagesConfig {})]));
+                }
+                Some("packages.lock.json") => {
+                    sources.push((
+                        path,
+                        vec![Box::new(PackagesLock {
+                            include_transitive: options.include_transitive,
+                        })],
+                    ));
+                }
+                Some(_) if is_msbuild_project(&path)? => {
+                    let directory_build_props = find_nearest_file(&path, "Directory.Build.props")?;
+                    let directory_packages_props =
+                        find_nearest_file(&path, "Directory.Packages.props")?;
+                    sources.push((
+                        path,
+                        vec![Box::new(CsProj {
+                            directory_build_props,
+                            directory_packages_props,
+                        })],
+                    ));
+                }
+                Some(file_name_str) if is_compose_file(file_name_str) => {
+                    sources.push((path, vec![Box::new(DockerCompose {})]));
+                }
+                Some("values.yaml") | Some("values.yml") => {
+                    sources.push((path, vec![Box::new(HelmValues {})]));
+                }
+                Some(_) if is_kubernetes_manifest(&path)? => {
+                    sources.push((path, vec![Box::new(Kubernetes {})]));
                 }
                 Some(file_name_str) if file_name_str.contains("Dockerfile") => {
-                    sources.push((path, vec![Box::new(DockerBase {}), Box::new(Apt {})]));
+                    sources.push((
+                        path,
+                        vec![Box::new(Dockerfile {
+                            build_args: options.build_args.to_owned(),
+                            parsers: vec![
+                                Box::new(DockerBase {}),
+                                Box::new(Apt {}),
+                                Box::new(Apk {}),
+                                Box::new(Rpm {}),
+                                Box::new(Pip {}),
+                                Box::new(NpmGlobal {}),
+                                Box::new(Gem {}),
+                            ],
+                        })],
+                    ));
                 }
                 _ => {}
             }
@@ -107,6 +302,252 @@ fn scan_dirs_recursively(
     Ok(sources)
 }
 
+fn read_sibling_file(path: &Path, file_name: &str) -> Result<Option<String>, Error> {
+    let sibling = match path.parent() {
+        Some(dir) => dir.join(file_name),
+        None => return Ok(None),
+    };
+    match sibling.is_file() {
+        true => Ok(Some(fs::read_to_string(sibling)?)),
+        false => Ok(None),
+    }
+}
+
+/// Reads the package.json next to a yarn.lock, followed by those of its workspaces.
+fn find_yarn_package_jsons(lock_path: &Path) -> Result<Vec<String>, Error> {
+    let (dir, root_package_json) = match (
+        lock_path.parent(),
+        read_sibling_file(lock_path, "package.json")?,
+    ) {
+        (Some(dir), Some(root_package_json)) => (dir, root_package_json),
+        _ => return Ok(vec![]),
+    };
+    let mut workspace_dirs = BTreeSet::new();
+    for pattern in package_json::workspaces(&root_package_json) {
+        workspace_dirs.extend(expand_workspace_pattern(dir, &pattern)?);
+    }
+    let mut package_jsons = vec![root_package_json];
+    for workspace_dir in workspace_dirs {
+        let package_json = workspace_dir.join("package.json");
+        if workspace_dir != dir && package_json.is_file() {
+            package_jsons.push(fs::read_to_string(package_json)?);
+        }
+    }
+    Ok(package_jsons)
+}
+
+/// Reads the manifest next to a Cargo.lock and those of its workspace members.
+fn find_cargo_lock_manifests(lock_path: &Path) -> Result<Vec<String>, Error> {
+    let (dir, root_manifest) = match (
+        lock_path.parent(),
+        read_sibling_file(lock_path, "Cargo.toml")?,
+    ) {
+        (Some(dir), Some(root_manifest)) => (dir, root_manifest),
+        _ => return Ok(vec![]),
+    };
+    let mut member_dirs = BTreeSet::new();
+    for pattern in cargo::workspace_members(&root_manifest) {
+        member_dirs.extend(expand_workspace_pattern(dir, &pattern)?);
+    }
+    let mut manifests = vec![root_manifest];
+    for member_dir in member_dirs {
+        let manifest = member_dir.join("Cargo.toml");
+        if member_dir != dir && manifest.is_file() {
+            manifests.push(fs::read_to_string(manifest)?);
+        }
+    }
+    Ok(manifests)
+}
+
+/// Finds the directories matching a workspace pattern, in which `*` matches part of a
+/// directory name and `**` any number of nested directories. Negated patterns are ignored.
+fn expand_workspace_pattern(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+    if pattern.starts_with('!') {
+        return Ok(vec![]);
+    }
+    let mut dirs = vec![root.to_owned()];
+    for segment in pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+    {
+        let mut matching_dirs = Vec::new();
+        for dir in dirs {
+            match segment {
+                "**" => collect_dirs_recursively(&dir, &mut matching_dirs)?,
+                _ if segment.contains('*') => {
+                    let segment_regex = Regex::new(&format!(
+                        "^{}$",
+                        regex::escape(segment).replace(r"\*", ".*")
+                    ))
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+                    for subdir in subdirs(&dir)? {
+                        let matches = subdir
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .map(|name| segment_regex.is_match(name))
+                            .unwrap_or(false);
+                        if matches {
+                            matching_dirs.push(subdir);
+                        }
+                    }
+                }
+                _ => {
+                    let subdir = dir.join(segment);
+                    if subdir.is_dir() {
+                        matching_dirs.push(subdir);
+                    }
+                }
+            }
+        }
+        dirs = matching_dirs;
+    }
+    Ok(dirs)
+}
+
+fn collect_dirs_recursively(dir: &Path, dirs: &mut Vec<PathBuf>) -> Result<(), Error> {
+    dirs.push(dir.to_owned());
+    for subdir in subdirs(dir)? {
+        collect_dirs_recursively(&subdir, dirs)?;
+    }
+    Ok(())
+}
+
+fn subdirs(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut subdirs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && entry.file_name() != "node_modules" {
+            subdirs.push(entry.path());
+        }
+    }
+    Ok(subdirs)
+}
+
+fn is_compose_file(file_name: &str) -> bool {
+    (file_name.starts_with("docker-compose") || file_name.starts_with("compose."))
+        && (file_name.ends_with(".yml") || file_name.ends_with(".yaml"))
+}
+
+fn is_kubernetes_manifest(path: &Path) -> Result<bool, Error> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yml") | Some("yaml") => Ok(kubernetes::is_manifest(&fs::read_to_string(path)?)),
+        _ => Ok(false),
+    }
+}
+
+/// Projects are matched by extension, while shared `.props` and `.targets` files are only
+/// parsed when they reference packages.
+fn is_msbuild_project(path: &Path) -> Result<bool, Error> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("csproj") | Some("fsproj") | Some("vbproj") => Ok(true),
+        Some("props") | Some("targets") => {
+            Ok(fs::read_to_string(path)?.contains("<PackageReference"))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Looks for a file in the directory of the given path and then in each parent directory, also
+/// above the scanned directory.
+fn find_nearest_file(path: &Path, file_name: &str) -> Result<Option<String>, Error> {
+    let dir = match path.parent() {
+        Some(dir) => fs::canonicalize(dir)?,
+        None => return Ok(None),
+    };
+    for dir in dir.ancestors() {
+        let candidate = dir.join(file_name);
+        if candidate.is_file() {
+            return Ok(Some(fs::read_to_string(candidate)?));
+        }
+    }
+    Ok(None)
+}
+
+fn find_cargo_workspace_manifest(manifest_path: &Path) -> Result<Option<String>, Error> {
+    let crate_dir = match manifest_path.parent() {
+        Some(crate_dir) => fs::canonicalize(crate_dir)?,
+        None => return Ok(None),
+    };
+    for dir in crate_dir.ancestors().skip(1) {
+        let candidate = dir.join("Cargo.toml");
+        if !candidate.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(&candidate)?;
+        if cargo::is_workspace_manifest(&content) {
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+/// Parent POMs are looked up by their relative path within `scan_root`, and only used when
+/// their coordinates match those given in `<parent>`.
+fn find_maven_parent_poms(scan_root: &Path, pom_path: &Path) -> Result<Vec<String>, Error> {
+    let mut parent_poms = Vec::new();
+    let mut visited = HashSet::new();
+    let mut path = pom_path.to_owned();
+    let mut content = fs::read_to_string(&path)?;
+    while let Some(relative_path) = pom::parent_relative_path(&content) {
+        let mut parent_path = match path.parent() {
+            Some(dir) => dir.join(relative_path),
+            None => break,
+        };
+        if parent_path.is_dir() {
+            parent_path = parent_path.join("pom.xml");
+        }
+        if !parent_path.is_file() {
+            break;
+        }
+        let canonical_path = fs::canonicalize(&parent_path)?;
+        if !canonical_path.starts_with(fs::canonicalize(scan_root)?)
+            || !visited.insert(canonical_path)
+        {
+            break;
+        }
+        let parent_content = fs::read_to_string(&parent_path)?;
+        if !pom::is_parent(&content, &parent_content) {
+            break;
+        }
+        parent_poms.push(parent_content.to_owned());
+        content = parent_content;
+        path = parent_path;
+    }
+    Ok(parent_poms)
+}
+
+/// Reads the files included with `-r`, following nested includes and skipping cycles.
+fn find_included_requirements(requirements_path: &Path) -> Result<Vec<String>, Error> {
+    let mut included_requirements = Vec::new();
+    let mut visited = HashSet::new();
+    visited.insert(fs::canonicalize(requirements_path)?);
+    let mut pending = vec![requirements_path.to_owned()];
+    while let Some(path) = pending.pop() {
+        let content = fs::read_to_string(&path)?;
+        for included_file in requirements::included_files(&content) {
+            let included_path = match path.parent() {
+                Some(dir) => dir.join(included_file),
+                None => PathBuf::from(included_file),
+            };
+            if !included_path.is_file() {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Included requirements file not found: {}",
+                        included_path.display()
+                    ),
+                ));
+            }
+            if !visited.insert(fs::canonicalize(&included_path)?) {
+                continue;
+            }
+            included_requirements.push(fs::read_to_string(&included_path)?);
+            pending.push(included_path);
+        }
+    }
+    Ok(included_requirements)
+}
+
 fn relative_path<P: AsRef<Path>>(full_path: P, root_path: P) -> Result<String, SouperIoError> {
     let relative_path = match utils::relative_path(full_path.as_ref(), root_path.as_ref()) {
         Ok(relative_path) => relative_path,
@@ -131,3 +572,34 @@ fn relative_path<P: AsRef<Path>>(full_path: P, root_path: P) -> Result<String, S
     let relative_path = relative_path.replace('\\', "/");
     Ok(relative_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("App.csproj", true)]
+    #[test_case("App.fsproj", true)]
+    #[test_case("App.vbproj", true)]
+    #[test_case("App.csproj.user", false)]
+    #[test_case("App.csproj.bak", false)]
+    #[test_case("App.sln", false)]
+    fn msbuild_project_extensions(file_name: &str, expected: bool) {
+        let path = Path::new("does-not-exist").join(file_name);
+        assert_eq!(expected, is_msbuild_project(&path).unwrap());
+    }
+
+    #[test]
+    fn nearest_file_in_parent_directory() {
+        let dir = std::env::temp_dir().join(format!("souper-nearest-file-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src").join("App")).unwrap();
+        fs::write(dir.join("Directory.Packages.props"), "<Project />").unwrap();
+        let path = dir.join("src").join("App").join("App.csproj");
+
+        let found = find_nearest_file(&path, "Directory.Packages.props");
+        let missing = find_nearest_file(&path, "Directory.Build.props");
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Some("<Project />".to_owned()), found.unwrap());
+        assert_eq!(None, missing.unwrap());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::soup::model::Soup;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub contexts_added: Vec<String>,
+    pub contexts_removed: Vec<String>,
+    pub contexts: BTreeMap<String, ContextChanges>,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct ContextChanges {
+    pub soups_added: Vec<SoupVersion>,
+    pub soups_removed: Vec<SoupVersion>,
+    pub version_changes: Vec<VersionChange>,
+    pub meta_keys_added: Vec<MetaKeys>,
+    pub meta_keys_updated: Vec<MetaKeys>,
+    pub meta_keys_removed: Vec<MetaKeys>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct SoupVersion {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct VersionChange {
+    pub name: String,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct MetaKeys {
+    pub name: String,
+    pub keys: Vec<String>,
+}
+
+impl From<&Soup> for SoupVersion {
+    fn from(soup: &Soup) -> Self {
+        SoupVersion {
+            name: soup.name.to_owned(),
+            version: soup.version.to_owned(),
+        }
+    }
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.contexts_added.is_empty()
+            && self.contexts_removed.is_empty()
+            && self.contexts.values().all(ContextChanges::is_empty)
+    }
+
+    pub fn context(&mut self, path: &str) -> &mut ContextChanges {
+        self.contexts.entry(path.to_owned()).or_default()
+    }
+}
+
+impl ContextChanges {
+    pub fn is_empty(&self) -> bool {
+        self.soups_added.is_empty()
+            && self.soups_removed.is_empty()
+            && self.version_changes.is_empty()
+            && self.meta_keys_added.is_empty()
+            && self.meta_keys_updated.is_empty()
+            && self.meta_keys_removed.is_empty()
+    }
+}
+
+impl fmt::Display for ChangeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for path in &self.contexts_added {
+            writeln!(f, "Added: {}", path)?;
+        }
+        for path in &self.contexts_removed {
+            writeln!(f, "Removed: {}", path)?;
+        }
+        for (path, changes) in self.contexts.iter().filter(|(_, c)| !c.is_empty()) {
+            writeln!(f, "{}", path)?;
+            for soup in &changes.soups_added {
+                writeln!(f, "  + {} ({})", soup.name, soup.version)?;
+            }
+            for soup in &changes.soups_removed {
+                writeln!(f, "  - {} ({})", soup.name, soup.version)?;
+            }
+            for change in &changes.version_changes {
+                writeln!(
+                    f,
+                    "  ~ {} ({} -> {})",
+                    change.name, change.old_version, change.new_version
+                )?;
+            }
+            for meta in &changes.meta_keys_added {
+                writeln!(
+                    f,
+                    "  ~ {} (meta keys added: {})",
+                    meta.name,
+                    meta.keys.join(", ")
+                )?;
+            }
+            for meta in &changes.meta_keys_updated {
+                writeln!(
+                    f,
+                    "  ~ {} (meta keys updated: {})",
+                    meta.name,
+                    meta.keys.join(", ")
+                )?;
+            }
+            for meta in &changes.meta_keys_removed {
+                writeln!(
+                    f,
+                    "  ~ {} (meta keys removed: {})",
+                    meta.name,
+                    meta.keys.join(", ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let mut change_set = ChangeSet::default();
+        change_set.context("src/package.json");
+        assert_eq!(true, change_set.is_empty());
+        assert_eq!("", change_set.to_string());
+    }
+
+    #[test]
+    fn only_meta_keys_updated() {
+        let mut change_set = ChangeSet::default();
+        change_set
+            .context("src/package.json")
+            .meta_keys_updated
+            .push(MetaKeys {
+                name: "some-dep".to_owned(),
+                keys: vec!["manufacturer".to_owned()],
+            });
+        assert_eq!(false, change_set.is_empty());
+        assert_eq!(
+            "src/package.json\n  ~ some-dep (meta keys updated: manufacturer)\n",
+            change_set.to_string()
+        );
+    }
+
+    #[test]
+    fn display() {
+        let mut change_set = ChangeSet::default();
+        change_set.contexts_added.push("src/Dockerfile".to_owned());
+        change_set
+            .context("src/Dockerfile")
+            .soups_added
+            .push(SoupVersion {
+                name: "postgres".to_owned(),
+                version: "14.4".to_owned(),
+            });
+        let changes = change_set.context("src/package.json");
+        changes.soups_removed.push(SoupVersion {
+            name: "some-dep".to_owned(),
+            version: "1.0.0".to_owned(),
+        });
+        changes.version_changes.push(VersionChange {
+            name: "another-dep".to_owned(),
+            old_version: "1.0.0".to_owned(),
+            new_version: "1.2.0".to_owned(),
+        });
+        changes.meta_keys_added.push(MetaKeys {
+            name: "another-dep".to_owned(),
+            keys: vec!["requirements".to_owned(), "manufacturer".to_owned()],
+        });
+        changes.meta_keys_updated.push(MetaKeys {
+            name: "some-other-dep".to_owned(),
+            keys: vec!["requirements".to_owned()],
+        });
+        changes.meta_keys_removed.push(MetaKeys {
+            name: "some-other-dep".to_owned(),
+            keys: vec!["target".to_owned()],
+        });
+        assert_eq!(
+            r#"Added: src/Dockerfile
+src/Dockerfile
+  + postgres (14.4)
+src/package.json
+  - some-dep (1.0.0)
+  ~ another-dep (1.0.0 -> 1.2.0)
+  ~ another-dep (meta keys added: requirements, manufacturer)
+  ~ some-other-dep (meta keys updated: requirements)
+  ~ some-other-dep (meta keys removed: target)
+"#,
+            change_set.to_string()
+        );
+    }
+}
@@ -1,39 +1,150 @@
+use crate::soup::change_set::{ChangeSet, ContextChanges, MetaKeys, SoupVersion, VersionChange};
 use crate::soup::model::{Soup, SoupContexts};
 use serde_json::{Map, Value};
 use std::collections::btree_map::Entry;
 use std::collections::{BTreeSet, HashMap};
 
+/// Meta keys that are only ever set by parsers, never filled in by users.
+const PARSER_META_KEYS: [&str; 18] = [
+    "artifact",
+    "checksum",
+    "configuration",
+    "content-hash",
+    "dependency-kind",
+    "digest",
+    "environment-marker",
+    "group",
+    "hashes",
+    "integrity",
+    "registry",
+    "replaced-by",
+    "repository",
+    "resolved",
+    "scope",
+    "tag",
+    "target",
+    "target-framework",
+];
+
 impl SoupContexts {
-    pub fn apply(&mut self, other: SoupContexts) {
-        self.contexts
-            .retain(|path, _| other.contexts().contains_key(path));
+    pub fn apply(&mut self, other: SoupContexts) -> ChangeSet {
+        let mut change_set = ChangeSet::default();
+        // Contexts without SOUPs are left out when writing, so they are handled as missing
+        let removed_paths = self
+            .contexts
+            .keys()
+            .filter(|path| !matches!(other.contexts().get(*path), Some(soups) if !soups.is_empty()))
+            .cloned()
+            .collect::<Vec<String>>();
+        for path in removed_paths {
+            if let Some(soups) = self.contexts.remove(&path) {
+                change_set.contexts_removed.push(path.to_owned());
+                change_set
+                    .context(&path)
+                    .soups_removed
+                    .extend(soups.iter().map(SoupVersion::from));
+            }
+        }
 
-        let mut other_contexts = other.contexts.into_iter().collect::<Vec<(_, _)>>();
+        let mut other_contexts = other
+            .contexts
+            .into_iter()
+            .filter(|(_, soups)| !soups.is_empty())
+            .collect::<Vec<(_, _)>>();
         while let Some((path, other_soups)) = other_contexts.pop() {
             let (path, self_soups) = match self.contexts.entry(path) {
                 Entry::Vacant(entry) => {
+                    change_set.contexts_added.push(entry.key().to_owned());
+                    change_set
+                        .context(entry.key())
+                        .soups_added
+                        .extend(other_soups.iter().map(SoupVersion::from));
                     entry.insert(other_soups);
                     continue;
                 }
                 Entry::Occupied(entry) => entry.remove_entry(),
             };
-            self.contexts
-                .insert(path, combine_soups(self_soups, other_soups));
+            let soups = combine_soups(self_soups, other_soups, change_set.context(&path));
+            self.contexts.insert(path, soups);
         }
+
+        change_set.contexts_added.sort();
+        change_set.contexts.retain(|_, changes| !changes.is_empty());
+        change_set
     }
 }
 
-fn combine_soups(base: BTreeSet<Soup>, other: BTreeSet<Soup>) -> BTreeSet<Soup> {
-    let mut base_meta = base
-        .into_iter()
-        .map(|soup| (soup.name, soup.meta))
-        .collect::<HashMap<String, Map<String, Value>>>();
-    other
+/// SOUPs are paired by name and version first, so that a context may hold several versions
+/// of the same name. Those left over are paired by name only, as version changes.
+fn combine_soups(
+    base: BTreeSet<Soup>,
+    other: BTreeSet<Soup>,
+    changes: &mut ContextChanges,
+) -> BTreeSet<Soup> {
+    let mut base_soups: HashMap<String, Vec<Soup>> = HashMap::new();
+    for soup in base {
+        base_soups
+            .entry(soup.name.to_owned())
+            .or_default()
+            .push(soup);
+    }
+    let mut pairs = other
         .into_iter()
         .map(|other_soup| {
-            let meta = match base_meta.remove(&other_soup.name) {
-                Some(self_meta) => combine_meta(self_meta, other_soup.meta),
-                None => other_soup.meta,
+            let base_soup = base_soups.get_mut(&other_soup.name).and_then(|soups| {
+                let index = soups
+                    .iter()
+                    .position(|soup| soup.version == other_soup.version)?;
+                Some(soups.remove(index))
+            });
+            (other_soup, base_soup)
+        })
+        .collect::<Vec<(Soup, Option<Soup>)>>();
+    for (other_soup, base_soup) in pairs.iter_mut().filter(|(_, base)| base.is_none()) {
+        if let Some(soups) = base_soups.get_mut(&other_soup.name) {
+            if !soups.is_empty() {
+                *base_soup = Some(soups.remove(0));
+            }
+        }
+    }
+    let soups = pairs
+        .into_iter()
+        .map(|(other_soup, base_soup)| {
+            let meta = match base_soup {
+                Some(base_soup) => {
+                    if base_soup.version != other_soup.version {
+                        changes.version_changes.push(VersionChange {
+                            name: other_soup.name.to_owned(),
+                            old_version: base_soup.version,
+                            new_version: other_soup.version.to_owned(),
+                        });
+                    }
+                    let (meta, added_keys, updated_keys, removed_keys) =
+                        combine_meta(base_soup.meta, other_soup.meta);
+                    if !added_keys.is_empty() {
+                        changes.meta_keys_added.push(MetaKeys {
+                            name: other_soup.name.to_owned(),
+                            keys: added_keys,
+                        });
+                    }
+                    if !updated_keys.is_empty() {
+                        changes.meta_keys_updated.push(MetaKeys {
+                            name: other_soup.name.to_owned(),
+                            keys: updated_keys,
+                        });
+                    }
+                    if !removed_keys.is_empty() {
+                        changes.meta_keys_removed.push(MetaKeys {
+                            name: other_soup.name.to_owned(),
+                            keys: removed_keys,
+                        });
+                    }
+                    meta
+                }
+                None => {
+                    changes.soups_added.push(SoupVersion::from(&other_soup));
+                    other_soup.meta
+                }
             };
             Soup {
                 name: other_soup.name,
@@ -41,17 +152,54 @@ fn combine_soups(base: BTreeSet<Soup>, other: BTreeSet<Soup>) -> BTreeSet<Soup>
                 meta,
             }
         })
-        .collect::<BTreeSet<Soup>>()
+        .collect::<BTreeSet<Soup>>();
+    let mut removed_soups = base_soups
+        .into_values()
+        .flatten()
+        .map(|soup| SoupVersion::from(&soup))
+        .collect::<Vec<SoupVersion>>();
+    removed_soups.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
+    changes.soups_removed.append(&mut removed_soups);
+    soups
 }
 
-fn combine_meta(mut base: Map<String, Value>, other: Map<String, Value>) -> Map<String, Value> {
+/// Keys missing in `base` are added from `other`. Values in `other` that are
+/// neither empty nor null are produced by a parser, and overwrite stale values in `base`.
+/// Keys only ever set by parsers are removed from `base` when missing in `other`.
+fn combine_meta(
+    mut base: Map<String, Value>,
+    other: Map<String, Value>,
+) -> (Map<String, Value>, Vec<String>, Vec<String>, Vec<String>) {
+    let mut added_keys = Vec::new();
+    let mut updated_keys = Vec::new();
+    let removed_keys = PARSER_META_KEYS
+        .iter()
+        .filter(|key| !other.contains_key(**key) && base.remove(**key).is_some())
+        .map(|key| key.to_string())
+        .collect::<Vec<String>>();
     let mut patch = other.into_iter().collect::<Vec<(String, Value)>>();
     while let Some((key, value)) = patch.pop() {
-        if let serde_json::map::Entry::Vacant(entry) = base.entry(key) {
-            entry.insert(value);
+        match base.entry(key) {
+            serde_json::map::Entry::Vacant(entry) => {
+                added_keys.push(entry.key().to_owned());
+                entry.insert(value);
+            }
+            serde_json::map::Entry::Occupied(mut entry) => {
+                let is_placeholder = match &value {
+                    Value::Null => true,
+                    Value::String(value) => value.is_empty(),
+                    _ => false,
+                };
+                if !is_placeholder && *entry.get() != value {
+                    updated_keys.push(entry.key().to_owned());
+                    entry.insert(value);
+                }
+            }
         }
     }
-    base
+    added_keys.reverse();
+    updated_keys.reverse();
+    (base, added_keys, updated_keys, removed_keys)
 }
 
 #[cfg(test)]
@@ -290,4 +438,283 @@ mod tests {
         assert_eq!("1.0.0", soup.version);
         assert_eq!(meta(vec![("requirements", "a-requirement")]), soup.meta);
     }
+
+    #[test]
+    fn change_set_contexts() {
+        let mut base = create_contexts(
+            "src/package.json",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![]),
+            }],
+        );
+        let other = create_contexts(
+            "src/Dockerfile",
+            vec![Soup {
+                name: "postgres".to_owned(),
+                version: "14.4".to_owned(),
+                meta: meta(vec![]),
+            }],
+        );
+
+        let change_set = base.apply(other);
+        assert_eq!(vec!["src/Dockerfile".to_owned()], change_set.contexts_added);
+        assert_eq!(
+            vec!["src/package.json".to_owned()],
+            change_set.contexts_removed
+        );
+        assert_eq!(
+            vec![SoupVersion {
+                name: "postgres".to_owned(),
+                version: "14.4".to_owned()
+            }],
+            change_set.contexts["src/Dockerfile"].soups_added
+        );
+        assert_eq!(
+            vec![SoupVersion {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned()
+            }],
+            change_set.contexts["src/package.json"].soups_removed
+        );
+    }
+
+    #[test]
+    fn change_set_empty_contexts() {
+        let mut base = create_contexts("src/package.json", vec![]);
+        let change_set = base.apply(empty_contexts());
+        assert_eq!(
+            vec!["src/package.json".to_owned()],
+            change_set.contexts_removed
+        );
+        assert_eq!(false, change_set.is_empty());
+        assert_eq!(true, base.contexts.is_empty());
+
+        let mut base = empty_contexts();
+        let change_set = base.apply(create_contexts("src/package.json", vec![]));
+        assert_eq!(true, change_set.is_empty());
+        assert_eq!(true, base.contexts.is_empty());
+    }
+
+    #[test]
+    fn change_set_soups() {
+        let mut base = create_contexts(
+            "src/package.json",
+            vec![
+                Soup {
+                    name: "some-dep".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    meta: meta(vec![("requirements", "a-requirement")]),
+                },
+                Soup {
+                    name: "removed-dep".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    meta: meta(vec![]),
+                },
+                Soup {
+                    name: "multi-dep".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    meta: meta(vec![("tag", "1.0.0")]),
+                },
+                Soup {
+                    name: "multi-dep".to_owned(),
+                    version: "2.0.0".to_owned(),
+                    meta: meta(vec![("tag", "2.0.0")]),
+                },
+            ],
+        );
+        let other = create_contexts(
+            "src/package.json",
+            vec![
+                Soup {
+                    name: "some-dep".to_owned(),
+                    version: "1.2.0".to_owned(),
+                    meta: meta(vec![("requirements", ""), ("manufacturer", "")]),
+                },
+                Soup {
+                    name: "multi-dep".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    meta: meta(vec![("tag", "1.0.0")]),
+                },
+                Soup {
+                    name: "multi-dep".to_owned(),
+                    version: "3.0.0".to_owned(),
+                    meta: meta(vec![("tag", "3.0.0")]),
+                },
+                Soup {
+                    name: "added-dep".to_owned(),
+                    version: "2.0.0".to_owned(),
+                    meta: meta(vec![]),
+                },
+            ],
+        );
+
+        let change_set = base.apply(other);
+        assert_eq!(true, change_set.contexts_added.is_empty());
+        assert_eq!(true, change_set.contexts_removed.is_empty());
+        assert_eq!(
+            ContextChanges {
+                soups_added: vec![SoupVersion {
+                    name: "added-dep".to_owned(),
+                    version: "2.0.0".to_owned()
+                }],
+                soups_removed: vec![SoupVersion {
+                    name: "removed-dep".to_owned(),
+                    version: "1.0.0".to_owned()
+                }],
+                version_changes: vec![
+                    VersionChange {
+                        name: "multi-dep".to_owned(),
+                        old_version: "2.0.0".to_owned(),
+                        new_version: "3.0.0".to_owned()
+                    },
+                    VersionChange {
+                        name: "some-dep".to_owned(),
+                        old_version: "1.0.0".to_owned(),
+                        new_version: "1.2.0".to_owned()
+                    }
+                ],
+                meta_keys_added: vec![MetaKeys {
+                    name: "some-dep".to_owned(),
+                    keys: vec!["manufacturer".to_owned()]
+                }],
+                meta_keys_updated: vec![MetaKeys {
+                    name: "multi-dep".to_owned(),
+                    keys: vec!["tag".to_owned()]
+                }],
+                meta_keys_removed: vec![],
+            },
+            change_set.contexts["src/package.json"]
+        );
+    }
+
+    #[test]
+    fn change_set_same_name_versions() {
+        let soups = vec![
+            Soup {
+                name: "node".to_owned(),
+                version: "16".to_owned(),
+                meta: meta(vec![("tag", "16")]),
+            },
+            Soup {
+                name: "node".to_owned(),
+                version: "18".to_owned(),
+                meta: meta(vec![("tag", "18")]),
+            },
+        ];
+        let mut base = create_contexts("Dockerfile", soups.clone());
+        let change_set = base.apply(create_contexts("Dockerfile", soups.clone()));
+        assert_eq!(true, change_set.is_empty());
+        assert_eq!(
+            soups.into_iter().collect::<BTreeSet<Soup>>(),
+            base.contexts["Dockerfile"]
+        );
+    }
+
+    #[test]
+    fn change_set_no_changes() {
+        let mut base = create_contexts(
+            "src/package.json",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![("requirements", "a-requirement")]),
+            }],
+        );
+        let other = create_contexts(
+            "src/package.json",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![("requirements", "")]),
+            }],
+        );
+
+        let change_set = base.apply(other);
+        assert_eq!(true, change_set.is_empty());
+    }
+
+    #[test]
+    fn update_parsed_meta() {
+        let mut base = create_contexts(
+            "Cargo.toml",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![
+                    ("dependency-kind", "dev"),
+                    ("requirements", "a-requirement"),
+                ]),
+            }],
+        );
+        let other = create_contexts(
+            "Cargo.toml",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![("dependency-kind", "normal"), ("requirements", "")]),
+            }],
+        );
+
+        let change_set = base.apply(other);
+        let soups = base.contexts.get("Cargo.toml").unwrap();
+        let soup = soups.iter().find(|s| s.name == "some-dep").unwrap();
+        assert_eq!(
+            meta(vec![
+                ("dependency-kind", "normal"),
+                ("requirements", "a-requirement")
+            ]),
+            soup.meta
+        );
+        assert_eq!(
+            vec![MetaKeys {
+                name: "some-dep".to_owned(),
+                keys: vec!["dependency-kind".to_owned()]
+            }],
+            change_set.contexts["Cargo.toml"].meta_keys_updated
+        );
+    }
+
+    #[test]
+    fn remove_parsed_meta() {
+        let mut base = create_contexts(
+            "Cargo.toml",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![
+                    ("dependency-kind", "normal"),
+                    ("target", "cfg(windows)"),
+                    ("requirements", "a-requirement"),
+                ]),
+            }],
+        );
+        let other = create_contexts(
+            "Cargo.toml",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![("dependency-kind", "normal"), ("requirements", "")]),
+            }],
+        );
+
+        let change_set = base.apply(other);
+        let soups = base.contexts.get("Cargo.toml").unwrap();
+        let soup = soups.iter().find(|s| s.name == "some-dep").unwrap();
+        assert_eq!(
+            meta(vec![
+                ("dependency-kind", "normal"),
+                ("requirements", "a-requirement")
+            ]),
+            soup.meta
+        );
+        assert_eq!(
+            vec![MetaKeys {
+                name: "some-dep".to_owned(),
+                keys: vec!["target".to_owned()]
+            }],
+            change_set.contexts["Cargo.toml"].meta_keys_removed
+        );
+    }
 }
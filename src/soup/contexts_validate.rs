@@ -0,0 +1,102 @@
+use crate::soup::model::{Soup, SoupContexts};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+impl SoupContexts {
+    pub fn missing_meta<'a>(
+        &'a self,
+        required_keys: &'a [String],
+    ) -> BTreeMap<&'a String, Vec<(&'a Soup, Vec<&'a String>)>> {
+        self.contexts
+            .iter()
+            .filter_map(|(path, soups)| {
+                let incomplete_soups = soups
+                    .iter()
+                    .filter_map(|soup| {
+                        let missing_keys = required_keys
+                            .iter()
+                            .filter(|key| is_empty(soup.meta.get(*key)))
+                            .collect::<Vec<&String>>();
+                        match missing_keys.is_empty() {
+                            true => None,
+                            false => Some((soup, missing_keys)),
+                        }
+                    })
+                    .collect::<Vec<(&Soup, Vec<&String>)>>();
+                match incomplete_soups.is_empty() {
+                    true => None,
+                    false => Some((path, incomplete_soups)),
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_empty(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(value)) => value.trim().is_empty(),
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_contexts(path: &str, soups: Vec<Soup>) -> SoupContexts {
+        SoupContexts {
+            contexts: [(path.to_owned(), soups.into_iter().collect())]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn soup(name: &str, meta: Value) -> Soup {
+        Soup {
+            name: name.to_owned(),
+            version: "1.0.0".to_owned(),
+            meta: meta.as_object().unwrap().clone(),
+        }
+    }
+
+    #[test]
+    fn all_keys_present() {
+        let contexts = create_contexts(
+            "src/package.json",
+            vec![soup("some-dep", json!({ "requirements": "Do this" }))],
+        );
+        let required_keys = vec!["requirements".to_owned()];
+        assert_eq!(true, contexts.missing_meta(&required_keys).is_empty());
+    }
+
+    #[test]
+    fn missing_empty_or_null() {
+        let contexts = create_contexts(
+            "src/package.json",
+            vec![
+                soup("missing-dep", json!({})),
+                soup("empty-dep", json!({ "requirements": "" })),
+                soup("null-dep", json!({ "requirements": null })),
+                soup("complete-dep", json!({ "requirements": "Do this" })),
+            ],
+        );
+        let required_keys = vec!["requirements".to_owned()];
+        let missing = contexts.missing_meta(&required_keys);
+        assert_eq!(1, missing.len());
+        let names = missing
+            .get(&"src/package.json".to_owned())
+            .unwrap()
+            .iter()
+            .map(|(soup, _)| soup.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["empty-dep", "missing-dep", "null-dep"], names);
+    }
+
+    #[test]
+    fn no_required_keys() {
+        let contexts = create_contexts("src/package.json", vec![soup("some-dep", json!({}))]);
+        assert_eq!(true, contexts.missing_meta(&[]).is_empty());
+    }
+}
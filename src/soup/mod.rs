@@ -1,3 +1,5 @@
+pub mod change_set;
 pub mod contexts_apply;
 pub mod contexts_io;
+pub mod contexts_validate;
 pub mod model;